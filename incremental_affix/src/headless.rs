@@ -0,0 +1,276 @@
+//! Running the game without a window for balance testing.
+//!
+//! A [Plan] is a script of steps carried out in order. Each step waits until its
+//! condition holds and it can actually be carried out, e.g. a craft waits until
+//! the recipe is known and affordable. While the plan runs, a timeline of stock
+//! values and story milestones is printed to stdout.
+
+use std::fmt::Display;
+use std::time::Duration;
+
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::time::common_conditions::on_timer;
+use serde::Deserialize;
+
+use crate::incremental::IncrementalPlugin;
+use crate::incremental::action::{Action, ChangeAction, KnownActions};
+use crate::incremental::item::{affixive_item::AffixiveItem, base::Base, craft::{CraftRequest, Recipe}, equipment::EquipRequest, item_slot::ItemSlot};
+use crate::incremental::job::{AssignFollowerRequest, FollowersAssigned, JobKind, UnassignFollowerRequest};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::{StockKind, stockyard::Stockyard};
+
+/// A scripted playthrough.
+#[derive(Debug, Deserialize)]
+pub struct Plan {
+    /// How many simulated seconds to run for.
+    seconds: f32,
+
+    /// How often to print the stockyard, in simulated seconds.
+    #[serde(default = "default_report_every")]
+    report_every: f32,
+
+    steps: Vec<PlanStep>,
+}
+
+fn default_report_every() -> f32 {
+    30.0
+}
+
+impl Plan {
+    /// Read a plan from a RON file.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let contents = std::fs::read_to_string(path).map_err(|error| format!("Unable to read {}: {}", path, error))?;
+        ron::from_str(&contents).map_err(|error| format!("Unable to parse {}: {}", path, error))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct PlanStep {
+    #[serde(default)]
+    when: Condition,
+    then: PlanCommand,
+}
+
+#[derive(Debug, Default, Deserialize)]
+enum Condition {
+    #[default]
+    Immediately,
+    AtSecond(f32),
+    StockAtLeast(StockKind, f64),
+}
+
+impl Condition {
+    fn is_met(&self, elapsed_seconds: f32, stockyard: &Stockyard) -> bool {
+        match *self {
+            Condition::Immediately => true,
+            Condition::AtSecond(seconds) => elapsed_seconds >= seconds,
+            Condition::StockAtLeast(stock_kind, amount) => stockyard[stock_kind] >= amount,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+enum PlanCommand {
+    /// Switch to the action. Waits until the action is known.
+    ChangeAction(Action),
+
+    /// Assign followers to the job. Waits until there are enough unassigned followers.
+    AssignFollowers(JobKind, u32),
+
+    /// Unassign followers from the job. Does not wait; unassigns at most as many as do the job.
+    UnassignFollowers(JobKind, u32),
+
+    /// Craft an item of the base. Waits until the recipe is known and affordable.
+    Craft(Base),
+
+    /// Equip an unequipped item of the base. Waits until there is such an item.
+    Equip(Base),
+}
+
+impl Display for PlanCommand {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PlanCommand::ChangeAction(action) => write!(f, "Start {}", action),
+            PlanCommand::AssignFollowers(job_kind, count) => write!(f, "Assign {} followers to {}", count, job_kind),
+            PlanCommand::UnassignFollowers(job_kind, count) => write!(f, "Unassign {} followers from {}", count, job_kind),
+            PlanCommand::Craft(base) => write!(f, "Craft {}", base),
+            PlanCommand::Equip(base) => write!(f, "Equip {}", base),
+        }
+    }
+}
+
+#[derive(Debug, Resource)]
+struct PlanProgress {
+    steps: Vec<PlanStep>,
+    next: usize,
+}
+
+/// Run the plan to completion, printing the timeline as it goes.
+pub fn run(plan: Plan) {
+    let mut app = App::new();
+    app
+    .add_plugins((MinimalPlugins, IncrementalPlugin))
+    .insert_resource(PlanProgress { steps: plan.steps, next: 0 })
+    .add_systems(Update, (
+        follow_plan,
+        report_log_entries,
+        report_known_actions,
+        report_new_recipes,
+        report_stockyard.run_if(on_timer(Duration::from_secs_f32(plan.report_every))),
+    ))
+    ;
+
+    // Each update advances time by exactly one fixed timestep.
+    let timestep = app.world().resource::<Time<Fixed>>().timestep();
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+    app.finish();
+    app.cleanup();
+
+    while app.world().resource::<Time>().elapsed_secs() < plan.seconds {
+        app.update();
+    }
+
+    let world = app.world();
+    print_stockyard(world.resource::<Time>().elapsed_secs(), world.resource::<Stockyard>());
+
+    let progress = world.resource::<PlanProgress>();
+    if let Some(step) = progress.steps.get(progress.next) {
+        println!("Plan unfinished. Stuck on step {}: {}", progress.next + 1, step.then);
+    }
+}
+
+fn timestamp(seconds: f32) -> String {
+    format!("[{:>8.2}s]", seconds)
+}
+
+fn follow_plan(
+    mut commands: Commands,
+    time: Res<Time>,
+
+    mut progress: ResMut<PlanProgress>,
+    stockyard: Res<Stockyard>,
+    known_actions: Res<KnownActions>,
+    followers_assigned: Res<FollowersAssigned>,
+
+    recipe_query: Query<(Entity, &Recipe)>,
+    item_query: Query<(Entity, &AffixiveItem)>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let Some(step) = progress.steps.get(progress.next) else { return; };
+
+    if !step.when.is_met(time.elapsed_secs(), &stockyard) {
+        return;
+    }
+
+    let carried_out = match step.then {
+        PlanCommand::ChangeAction(action) => {
+            let is_known = known_actions.contains(&action);
+            if is_known {
+                commands.trigger(ChangeAction::new(action));
+            }
+            is_known
+        },
+
+        PlanCommand::AssignFollowers(job_kind, count) => {
+            let has_enough = stockyard[StockKind::Followers].current() >= f64::from(**followers_assigned + count);
+            if has_enough {
+                for _ in 0..count {
+                    commands.trigger(AssignFollowerRequest { job_kind });
+                }
+            }
+            has_enough
+        },
+
+        PlanCommand::UnassignFollowers(job_kind, count) => {
+            for _ in 0..count {
+                commands.trigger(UnassignFollowerRequest { job_kind });
+            }
+            true
+        },
+
+        PlanCommand::Craft(base) => {
+            let recipe = recipe_query.iter()
+            .find(|(_, recipe)| recipe.base == base && recipe.resources.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount));
+
+            if let Some((recipe, _)) = recipe {
+                commands.trigger(CraftRequest { recipe });
+            }
+            recipe.is_some()
+        },
+
+        PlanCommand::Equip(base) => {
+            let equipped_items: Vec<Entity> = item_slot_query.iter().filter_map(|item_slot| item_slot.item).collect();
+
+            let item = item_query.iter()
+            .find(|(entity, item)| item.base() == base && !equipped_items.contains(entity));
+
+            match item {
+                Some((item_entity, item)) if item_slot_query.iter().any(|item_slot| item_slot.accepts(item)) => {
+                    commands.trigger(EquipRequest { item: item_entity, preferred_slot: None });
+                    true
+                },
+                _ => false,
+            }
+        },
+    };
+
+    if carried_out {
+        println!("{} Plan: {}", timestamp(time.elapsed_secs()), step.then);
+        progress.next += 1;
+    }
+}
+
+fn report_log_entries(
+    time: Res<Time>,
+    mut log_entries: MessageReader<LogEntry>,
+) {
+    for entry in log_entries.read() {
+        println!("{} Log: {}", timestamp(time.elapsed_secs()), entry.0);
+    }
+}
+
+fn report_known_actions(
+    time: Res<Time>,
+    known_actions: Res<KnownActions>,
+    mut reported: Local<HashSet<Action>>,
+) {
+    if !known_actions.is_changed() {
+        return;
+    }
+
+    for action in Action::LIST.iter().copied() {
+        if known_actions.contains(&action) && reported.insert(action) {
+            println!("{} Learned action: {}", timestamp(time.elapsed_secs()), action);
+        }
+    }
+}
+
+fn report_new_recipes(
+    time: Res<Time>,
+    recipe_query: Query<&Recipe, Added<Recipe>>,
+) {
+    for recipe in recipe_query.iter() {
+        println!("{} Recipe available: {}", timestamp(time.elapsed_secs()), recipe.base);
+    }
+}
+
+fn report_stockyard(
+    time: Res<Time>,
+    stockyard: Res<Stockyard>,
+) {
+    print_stockyard(time.elapsed_secs(), &stockyard);
+}
+
+fn print_stockyard(elapsed_seconds: f32, stockyard: &Stockyard) {
+    let mut line = timestamp(elapsed_seconds);
+
+    for stock_kind in StockKind::LIST.iter().copied() {
+        line.push_str(&format!(" {}: ", stock_kind));
+        stockyard[stock_kind].push_str_current_and_maximum(&mut line);
+    }
+
+    println!("{}", line);
+}
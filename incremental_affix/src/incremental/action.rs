@@ -4,12 +4,14 @@ use std::time::Duration;
 use bevy::ecs::VariantDefaults;
 use bevy::prelude::*;
 use bevy::platform::collections::HashSet;
+use serde::{Deserialize, Serialize};
 
 use crate::incremental::IncrementalStartupSystemSet;
 pub use crate::incremental::action::change::ResetPlayerAction;
 use crate::incremental::action::spc::PlayerActionSpc;
 use crate::incremental::affinity::Affinity;
-use crate::incremental::stock::producer_consumer::StockSystems;
+use crate::incremental::item::durability::wear_equipped_items;
+use crate::incremental::stock::producer_consumer::{StockSystems, StockTick};
 use crate::incremental::stock::{StockKind, stockyard::Stockyard};
 
 pub use change::ChangeAction;
@@ -34,8 +36,9 @@ impl Plugin for ActionPlugin {
         .add_observer(change::on_reset_player_action)
         .add_systems(Startup, spc::initialize_action_spc.in_set(IncrementalStartupSystemSet))
         .add_systems(FixedUpdate, (progress_system, affinity_check_system))
-        .add_systems(FixedUpdate, spc::preconsume.in_set(StockSystems::PreConsume))
-        .add_systems(FixedUpdate, spc::postconsume.in_set(StockSystems::PostConsume))
+        .add_systems(StockTick, spc::preconsume.in_set(StockSystems::PreConsume))
+        .add_systems(StockTick, change::update_gathering_stats.in_set(StockSystems::PostConsume).after(wear_equipped_items).before(spc::postconsume))
+        .add_systems(StockTick, spc::postconsume.in_set(StockSystems::PostConsume))
         ;
     }
 }
@@ -43,7 +46,7 @@ impl Plugin for ActionPlugin {
 /// An action the player can perform.
 /// 
 /// The player can only perform one action at a time.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, VariantDefaults, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, VariantDefaults, Default, Serialize, Deserialize)]
 pub enum Action {
     #[default] // To satisify using in BSN.
     Explore,
@@ -1,8 +1,9 @@
 use bevy::prelude::*;
 
 use crate::incremental::action::spc::PlayerActionSpc;
+use crate::incremental::item::{affixive_item::AffixiveItem, item_slot::ItemSlot, loadout::{Loadout, equip_loadout}};
 use crate::incremental::stock::StockKind;
-use crate::incremental::stats::PlayerActionsStats;
+use crate::incremental::stats::{PlayerActionStats, PlayerActionsStats, StatSources};
 use crate::incremental::action::{Action, ActionAffinity, ActionProgress, AffinityTimer, CurrentAction};
 use crate::incremental::DotPerSecond;
 
@@ -21,8 +22,9 @@ impl ChangeAction {
 
 pub(in super) fn on_change_action(
     event: On<ChangeAction>,
+    mut commands: Commands,
 
-    player_action_bonuses: Res<PlayerActionsStats>,
+    mut player_action_bonuses: ResMut<PlayerActionsStats>,
 
     mut current_action: ResMut<CurrentAction>,
     mut action_progress: ResMut<ActionProgress>,
@@ -30,12 +32,22 @@ pub(in super) fn on_change_action(
     mut affinity_timer: ResMut<AffinityTimer>,
 
     mut spc: Single<&mut PlayerActionSpc>,
+
+    loadout_query: Query<&Loadout>,
+    item_query: Query<&AffixiveItem>,
+    mut equipment: ParamSet<(Query<&mut ItemSlot>, StatSources)>,
 ) {
     // Changing to current action. Disregard.
     if Some(event.action) == current_action.0 {
         return;
     }
 
+    // The action's loadout is equipped first so that the action starts with its stats.
+    if let Some(loadout) = loadout_query.iter().find(|loadout| loadout.action == Some(event.action)) {
+        equip_loadout(commands.reborrow(), loadout, &mut equipment.p0(), &item_query);
+        *player_action_bonuses = equipment.p1().calculate();
+    }
+
     reset_player_action(&mut action_progress, &mut action_affinity, &mut affinity_timer, &mut current_action, &mut spc);
 
     current_action.set(event.action);
@@ -43,25 +55,10 @@ pub(in super) fn on_change_action(
 
     match event.action {
         Action::Explore => {},
-        Action::GatherWood => {
-            let bonuses = &player_action_bonuses.gather_wood;
-            spc.push_change(StockKind::Wood, bonuses.base_gain_per_second);
-            spc.set_affinity_multiplier(bonuses.affinity.multiplier);
-            action_affinity.affinity = bonuses.affinity;
-            affinity_timer.unpause();
-        },
-        Action::GatherStone => {
-            let bonuses = &player_action_bonuses.gather_stone;
-            spc.push_change(StockKind::Stone, bonuses.base_gain_per_second);
-            spc.set_affinity_multiplier(bonuses.affinity.multiplier);
-            action_affinity.affinity = bonuses.affinity;
-            affinity_timer.unpause();
-        },
-        Action::Hunt => {
-            let bonuses = &player_action_bonuses.hunt;
-            spc.push_change(StockKind::Carcass, bonuses.base_gain_per_second);
-            spc.set_affinity_multiplier(bonuses.affinity.multiplier);
-            action_affinity.affinity = bonuses.affinity;
+        Action::GatherWood | Action::GatherStone | Action::Hunt => {
+            let stock_kind = gathered_stock(event.action).expect("Gathering actions must gather a stock.");
+            let bonuses = player_action_bonuses.get_bonuses(event.action).expect("Gathering actions must have stats.");
+            push_gathering_changes(stock_kind, bonuses, &mut spc, &mut action_affinity);
             affinity_timer.unpause();
         },
         Action::RenderCarcass => {
@@ -78,6 +75,53 @@ pub(in super) fn on_change_action(
     }
 }
 
+/// Keeps the running gathering action in line with the player's stats,
+/// such as when equipment wears down or is swapped.
+///
+/// Runs in the [StockTick](crate::incremental::stock::producer_consumer::StockTick) schedule after items wear,
+/// so items breaking while fast-forwarding also change what is gathered.
+pub(in super) fn update_gathering_stats(
+    player_actions_stats: Res<PlayerActionsStats>,
+    current_action: Res<CurrentAction>,
+
+    mut action_affinity: ResMut<ActionAffinity>,
+    mut spc: Single<&mut PlayerActionSpc>,
+) {
+    if !player_actions_stats.is_changed() {
+        return;
+    }
+
+    let Some(action) = **current_action else { return; };
+    let Some(stock_kind) = gathered_stock(action) else { return; };
+
+    let bonuses = player_actions_stats.get_bonuses(action).expect("Gathering actions must have stats.");
+
+    spc.clear_changes();
+    push_gathering_changes(stock_kind, bonuses, &mut spc, &mut action_affinity);
+}
+
+/// The stock a gathering action gathers.
+fn gathered_stock(action: Action) -> Option<StockKind> {
+    match action {
+        Action::GatherWood => Some(StockKind::Wood),
+        Action::GatherStone => Some(StockKind::Stone),
+        Action::Hunt => Some(StockKind::Carcass),
+        _ => None,
+    }
+}
+
+fn push_gathering_changes(
+    stock_kind: StockKind,
+    bonuses: PlayerActionStats,
+
+    action_spc: &mut PlayerActionSpc,
+    action_affinity: &mut ActionAffinity,
+) {
+    action_spc.push_change(stock_kind, bonuses.base_gain_per_second);
+    action_spc.set_affinity_multiplier(bonuses.affinity.multiplier);
+    action_affinity.affinity = bonuses.affinity;
+}
+
 #[derive(Debug, Event)]
 pub struct ResetPlayerAction;
 
@@ -30,6 +30,11 @@ impl PlayerActionSpc {
         self.base_changes.clear();
     }
 
+    /// Remove the stock changes, keeping the affinity.
+    pub fn clear_changes(&mut self) {
+        self.base_changes.clear();
+    }
+
     pub fn push_change(&mut self, stock_kind: StockKind, change: PerSecond) {
         self.base_changes.push(StockPerSecond::new(stock_kind, change));
     }
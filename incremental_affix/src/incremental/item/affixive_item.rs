@@ -1,13 +1,14 @@
-use std::{borrow::Cow, fmt::Display, ops::Deref};
+use std::{borrow::Cow, fmt::Display, ops::{Deref, DerefMut}};
 
 use bevy::{ecs::component::Component, platform::collections::HashMap};
+use rand::seq::IndexedRandom as _;
+use serde::{Deserialize, Serialize};
 
-use crate::incremental::item::{base::{AffixiveItemBase, Base}, item_slot::ItemSlotTag, modifier::{Affix, Implicit, Modifier, ModifierValue, Prefix, Suffix}};
+use crate::incremental::item::{base::{AffixiveItemBase, Base}, definitions::{ArtifactDefinition, SetId}, rune::Rune, modifier::{Affix, AffixId, Implicit, Modifier, ModifierGroup, ModifierValue, Prefix, Suffix}};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Quality {
     /// An item with fixed affixes.
-    #[expect(unused)]
     FixedArtifact,
 
     /// An item with the specific number of prefixes and suffixes.
@@ -16,13 +17,101 @@ pub enum Quality {
     Quality(u8),
 }
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
-pub struct ImplicitIndex(pub usize);
+impl Quality {
+    /// Highest quality crafting can raise an item to.
+    pub const MAX: u8 = 3;
+}
+
+/// How powerful an item looks, from its quality and affixes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Rarity {
+    /// No prefixes or suffixes.
+    Normal,
+
+    /// Prefixes or suffixes on an item of quality 1 or lower.
+    Magic,
+
+    /// Prefixes or suffixes on an item of quality 2 or higher, with room for more than one of each.
+    Rare,
+
+    /// An item with fixed affixes.
+    Artifact,
+}
+
+impl Display for Rarity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Rarity::Normal => "Normal",
+            Rarity::Magic => "Magic",
+            Rarity::Rare => "Rare",
+            Rarity::Artifact => "Artifact",
+        })
+    }
+}
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+/// How much use an item has left, in seconds of its action running while it is equipped.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Durability {
+    pub current: f64,
+    pub maximum: f64,
+}
+
+impl Durability {
+    pub fn new(maximum: f64) -> Self {
+        Self { current: maximum, maximum }
+    }
+
+    /// Fraction of the durability left, from `0.0` to `1.0`.
+    pub fn fraction(&self) -> f64 {
+        if self.maximum <= 0.0 { 0.0 } else { (self.current / self.maximum).clamp(0.0, 1.0) }
+    }
+
+    /// Whole percent of the durability left, for noticing changes worth showing.
+    pub fn percent(&self) -> u32 {
+        (self.fraction() * 100.0).ceil() as u32
+    }
+}
+
+impl Display for Durability {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.0}/{:.0}", self.current.ceil(), self.maximum)
+    }
+}
+
+/// How far into the game an item was made.
+///
+/// Affix tiers with a level above the item level cannot roll on the item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemLevel(pub u32);
+
+impl ItemLevel {
+    pub const MIN: Self = Self(1);
+}
+
+impl Default for ItemLevel {
+    fn default() -> Self {
+        Self::MIN
+    }
+}
+
+impl Display for ItemLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub enum ItemTag {
     Tool,
     Hunt,
+    Garment,
+    Totem,
+    Relic,
+}
+
+impl ItemTag {
+    pub const LIST: &[Self] = &[Self::Tool, Self::Hunt, Self::Garment, Self::Totem, Self::Relic];
 }
 
 impl Display for ItemTag {
@@ -30,6 +119,9 @@ impl Display for ItemTag {
         let string = match self {
             ItemTag::Tool => "Tool",
             ItemTag::Hunt => "Hunting Weapon",
+            ItemTag::Garment => "Garment",
+            ItemTag::Totem => "Totem",
+            ItemTag::Relic => "Relic",
         };
 
         f.write_str(string)?;
@@ -37,22 +129,57 @@ impl Display for ItemTag {
     }
 }
 
-impl From<ItemSlotTag> for ItemTag {
-    fn from(slot_tag: ItemSlotTag) -> Self {
-        match slot_tag {
-            ItemSlotTag::Tool => ItemTag::Tool,
-            ItemSlotTag::Hunt => Self::Hunt
-        }
-    }
-}
+/// First words of the names of items with too many affixes to name after them.
+const RARE_NAME_FIRST_WORDS: &[&str] = &[
+    "Storm", "Ember", "Oak", "Granite", "Dusk", "Grim", "Bright", "Hollow", "Iron", "Moss",
+];
+
+/// Second words of the names of items with too many affixes to name after them.
+const RARE_NAME_SECOND_WORDS: &[&str] = &[
+    "Bite", "Song", "Grip", "Heart", "Whisper", "Fang", "Root", "Mark", "Call", "Edge",
+];
 
 #[derive(Debug, Clone, Copy)]
 pub(crate) enum PushAffixError {
     AffixiveItemIsFixed,
     AffixiveItemQualityTooLow,
+
+    /// The item already has an affix in the same modifier group.
+    ModifierGroupAlreadyPresent,
+
+    /// Every affix that could roll on the item is in a modifier group the item already has.
+    NoEligibleAffix,
 }
 
-#[derive(Debug, Component)]
+impl Display for PushAffixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            PushAffixError::AffixiveItemIsFixed => "You cannot modify the affixes of this.",
+            PushAffixError::AffixiveItemQualityTooLow => "Item quality too low.",
+            PushAffixError::ModifierGroupAlreadyPresent => "Item already has an affix like that.",
+            PushAffixError::NoEligibleAffix => "Item already has every affix that can roll on it.",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum SocketRuneError {
+    AffixiveItemIsFixed,
+    NoSockets,
+    SocketsFull,
+}
+
+impl Display for SocketRuneError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            SocketRuneError::AffixiveItemIsFixed => "You cannot socket runes into this.",
+            SocketRuneError::NoSockets => "Item has no sockets.",
+            SocketRuneError::SocketsFull => "Every socket of the item already has a rune.",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
 pub(crate) struct AffixiveItem {
     base: Base,
     name: Cow<'static, str>,
@@ -60,16 +187,40 @@ pub(crate) struct AffixiveItem {
     prefixes: Vec<Prefix>,
     suffixes: Vec<Suffix>,
     quality: Quality,
+
+    /// Added after the first saves were written, so those items are the lowest level.
+    #[serde(default)]
+    item_level: ItemLevel,
+
+    /// Name of a [Rarity::Rare] item, kept while it stays rare.
+    #[serde(default)]
+    rare_name: Option<String>,
+
+    /// Items without durability never wear out.
+    #[serde(default)]
+    durability: Option<Durability>,
+
+    /// Set the item is a piece of.
+    #[serde(default)]
+    set: Option<SetId>,
+
+    /// Number of runes that can be socketed into the item.
+    #[serde(default)]
+    sockets: u8,
+
+    #[serde(default)]
+    runes: Vec<Rune>,
+
     pub tags: Vec<ItemTag>,
 }
 
 impl AffixiveItem {
-    pub(crate) fn new(bases: &HashMap<Base, AffixiveItemBase>, implicits_db: &[Implicit], base: Base, quality: Quality) -> Self {
+    pub(crate) fn new(bases: &HashMap<Base, AffixiveItemBase>, implicits_db: &HashMap<AffixId, Implicit>, base: Base, quality: Quality, item_level: ItemLevel) -> Self {
         let item_base = &bases[&base];
 
         let mut implicits: Vec<Implicit>= item_base.implicits
             .iter()
-            .map(|ix| &implicits_db[ix.0])
+            .map(|id| &implicits_db[id])
             .cloned()
             .collect();
 
@@ -84,11 +235,69 @@ impl AffixiveItem {
             prefixes: vec![],
             suffixes: vec![],
             quality,
+            item_level,
+            rare_name: None,
+            durability: item_base.durability.map(Durability::new),
+            set: item_base.set.clone(),
+            sockets: item_base.sockets,
+            runes: vec![],
+            tags: item_base.tags.clone(),
+        }
+    }
+
+    /// Construct the artifact with its values rolled.
+    pub(crate) fn new_artifact(bases: &HashMap<Base, AffixiveItemBase>, definition: &ArtifactDefinition) -> Self {
+        let item_base = &bases[&definition.base];
+
+        Self {
+            base: definition.base,
+            name: definition.name.clone().into(),
+            implicits: definition.implicits.iter().map(|implicit| Implicit(implicit.rolled_affix())).collect(),
+            prefixes: definition.prefixes.iter().map(|prefix| Prefix(prefix.rolled_affix())).collect(),
+            suffixes: definition.suffixes.iter().map(|suffix| Suffix(suffix.rolled_affix())).collect(),
+            quality: Quality::FixedArtifact,
+            item_level: ItemLevel::default(),
+            rare_name: None,
+            durability: None,
+            set: definition.set.clone(),
+            sockets: 0,
+            runes: vec![],
             tags: item_base.tags.clone(),
         }
     }
 
-    pub fn name(&self) -> &str {
+    /// Name to show the player.
+    ///
+    /// Artifacts use their own name. Items with one prefix or suffix are named after them,
+    /// such as "Lumberjack's Stone Tools of Ingenuity". Items with more get a rare name.
+    pub fn name(&self) -> Cow<'_, str> {
+        if self.quality == Quality::FixedArtifact {
+            return Cow::Borrowed(&self.name);
+        }
+
+        if let Some(ref rare_name) = self.rare_name {
+            return Cow::Borrowed(rare_name);
+        }
+
+        let mut name = String::new();
+
+        if let Some(prefix) = self.prefixes.first() {
+            name.push_str(&prefix.name);
+            name.push(' ');
+        }
+
+        name.push_str(&self.name);
+
+        if let Some(suffix) = self.suffixes.first() {
+            name.push_str(" of ");
+            name.push_str(&suffix.name);
+        }
+
+        Cow::Owned(name)
+    }
+
+    /// Name of the base, or of the artifact for artifacts.
+    pub fn base_name(&self) -> &str {
         &self.name
     }
 
@@ -96,6 +305,103 @@ impl AffixiveItem {
         self.base
     }
 
+    pub fn item_level(&self) -> ItemLevel {
+        self.item_level
+    }
+
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    pub fn rarity(&self) -> Rarity {
+        match self.quality {
+            Quality::FixedArtifact => Rarity::Artifact,
+            _ if self.affix_count() == 0 => Rarity::Normal,
+            Quality::Quality(quality) if quality >= 2 => Rarity::Rare,
+            Quality::Quality(_) => Rarity::Magic,
+        }
+    }
+
+    pub fn durability(&self) -> Option<Durability> {
+        self.durability
+    }
+
+    /// Whether the item has worn out and cannot be equipped until repaired.
+    pub fn is_broken(&self) -> bool {
+        self.durability.is_some_and(|durability| durability.current <= 0.0)
+    }
+
+    /// How much less the item's gains are from wear, e.g. `-0.25` for 25% less.
+    ///
+    /// Worn items lose up to half their output as their durability runs out.
+    pub fn wear_penalty(&self) -> f64 {
+        self.durability.map_or(0.0, |durability| -0.5 * (1.0 - durability.fraction()))
+    }
+
+    /// Use up durability. Returns whether the item broke.
+    pub fn wear(&mut self, amount: f64) -> bool {
+        let Some(ref mut durability) = self.durability else { return false; };
+        let was_broken = durability.current <= 0.0;
+
+        durability.current = f64::max(durability.current - amount, 0.0);
+        !was_broken && durability.current <= 0.0
+    }
+
+    /// Restore the durability to its maximum.
+    pub fn repair(&mut self) {
+        if let Some(ref mut durability) = self.durability {
+            durability.current = durability.maximum;
+        }
+    }
+
+    pub fn set(&self) -> Option<&SetId> {
+        self.set.as_ref()
+    }
+
+    /// Which piece of its set the item is: the name of its base, or of the artifact.
+    ///
+    /// Only different pieces count towards set bonuses.
+    pub fn set_piece(&self) -> &str {
+        &self.name
+    }
+
+    pub fn sockets(&self) -> u8 {
+        self.sockets
+    }
+
+    pub fn runes(&self) -> &[Rune] {
+        &self.runes
+    }
+
+    /// Whether there is an empty socket for a rune.
+    pub fn can_socket(&self) -> Result<(), SocketRuneError> {
+        if self.quality == Quality::FixedArtifact {
+            Err(SocketRuneError::AffixiveItemIsFixed)
+        } else if self.sockets == 0 {
+            Err(SocketRuneError::NoSockets)
+        } else if self.runes.len() >= self.sockets as usize {
+            Err(SocketRuneError::SocketsFull)
+        } else {
+            Ok(())
+        }
+    }
+
+    pub fn try_socket(&mut self, rune: Rune) -> Result<(), SocketRuneError> {
+        self.can_socket()?;
+        self.runes.push(rune);
+        Ok(())
+    }
+
+    /// Take the rune out of the socket. Returns `None` if the socket is empty.
+    pub fn unsocket(&mut self, socket: usize) -> Option<Rune> {
+        (socket < self.runes.len()).then(|| self.runes.remove(socket))
+    }
+
+    /// Number of prefixes and suffixes on the item.
+    pub fn affix_count(&self) -> usize {
+        self.prefixes.len() + self.suffixes.len()
+    }
+
     pub fn implicits(&self) -> impl Iterator<Item=&Affix> {
         self.implicits.iter().map(Deref::deref)
     }
@@ -112,19 +418,21 @@ impl AffixiveItem {
     pub fn display(&self, bases: &HashMap<Base, AffixiveItemBase>) -> String {
         let mut output = String::new();
 
-        let name: &str = &self.name;
+        let name = self.name();
 
         match self.quality {
             Quality::FixedArtifact => {
-                output.push_str(&format!("Artifact: {}", name));
+                output.push_str(&format!("{}: {}", self.rarity(), name));
             },
 
             Quality::Quality(quality) => {
+                output.push_str(&format!("{}: ", self.rarity()));
+
                 for _ in 0..quality {
                     output.push('~');
                 }
 
-                output.push_str(name);
+                output.push_str(&name);
 
                 for _ in 0..quality {
                     output.push('~');
@@ -168,38 +476,158 @@ impl AffixiveItem {
         }
     }
 
+    /// Whether a prefix or suffix in the modifier group is on this item.
+    pub fn has_modifier_group(&self, group: &ModifierGroup) -> bool {
+        self.prefixes().chain(self.suffixes()).any(|affix| affix.group.as_ref() == Some(group))
+    }
+
+    /// Whether there is room for another prefix, ignoring modifier groups.
+    pub fn can_push_prefix(&self) -> Result<(), PushAffixError> {
+        self.has_room_for(self.prefixes.len())
+    }
+
+    /// Whether there is room for another suffix, ignoring modifier groups.
+    pub fn can_push_suffix(&self) -> Result<(), PushAffixError> {
+        self.has_room_for(self.suffixes.len())
+    }
+
+    fn has_room_for(&self, affix_count: usize) -> Result<(), PushAffixError> {
+        match self.quality {
+            Quality::FixedArtifact => Err(PushAffixError::AffixiveItemIsFixed),
+            Quality::Quality(quality) if quality as usize <= affix_count => Err(PushAffixError::AffixiveItemQualityTooLow),
+            _ => Ok(()),
+        }
+    }
+
+    fn check_modifier_group(&self, affix: &Affix) -> Result<(), PushAffixError> {
+        match affix.group {
+            Some(ref group) if self.has_modifier_group(group) => Err(PushAffixError::ModifierGroupAlreadyPresent),
+            _ => Ok(()),
+        }
+    }
+
     /// Attempt to attach a prefix to this item.
     /// 
     /// Return Ok(()) if the prefix was added.
     pub fn try_push_prefix(&mut self, prefix: Prefix) -> Result<(), PushAffixError> {
-        match self.quality {
-            Quality::FixedArtifact => Err(PushAffixError::AffixiveItemIsFixed),
-            Quality::Quality(quality) if quality as usize == self.prefixes.len() => Err(PushAffixError::AffixiveItemQualityTooLow),
-            _ => {
-                self.prefixes.push(prefix);
-                Ok(())
-            }
-        }
+        self.can_push_prefix()?;
+        self.check_modifier_group(&prefix)?;
+        self.prefixes.push(prefix);
+        self.update_rare_name();
+        Ok(())
     }
 
     /// Attempt to attach a suffix to this item.
     /// 
     /// Return Ok(()) if the suffix was added.
     pub fn try_push_suffix(&mut self, suffix: Suffix) -> Result<(), PushAffixError> {
-        match self.quality {
-            Quality::FixedArtifact => Err(PushAffixError::AffixiveItemIsFixed),
-            Quality::Quality(quality) if quality as usize == self.suffixes.len() => Err(PushAffixError::AffixiveItemQualityTooLow),
-            _ => {
-                self.suffixes.push(suffix);
-                Ok(())
-            }
+        self.can_push_suffix()?;
+        self.check_modifier_group(&suffix)?;
+        self.suffixes.push(suffix);
+        self.update_rare_name();
+        Ok(())
+    }
+
+    /// Remove every prefix and suffix from the item.
+    pub fn take_affixes(&mut self) -> (Vec<Prefix>, Vec<Suffix>) {
+        let affixes = (std::mem::take(&mut self.prefixes), std::mem::take(&mut self.suffixes));
+        self.update_rare_name();
+        affixes
+    }
+
+    /// Remove the prefix or suffix at the index, counting prefixes before suffixes.
+    pub fn remove_affix(&mut self, index: usize) -> Affix {
+        let affix = if index < self.prefixes.len() {
+            self.prefixes.remove(index).0
+        } else {
+            self.suffixes.remove(index - self.prefixes.len()).0
+        };
+
+        self.update_rare_name();
+        affix
+    }
+
+    /// Give the item a rare name once its affixes no longer fit in its name, and take it away when they do.
+    fn update_rare_name(&mut self) {
+        if self.prefixes.len() <= 1 && self.suffixes.len() <= 1 {
+            self.rare_name = None;
+        } else if self.rare_name.is_none() {
+            let mut rng = rand::rng();
+            let first = RARE_NAME_FIRST_WORDS.choose(&mut rng).expect("Rare name word lists must not be empty.");
+            let second = RARE_NAME_SECOND_WORDS.choose(&mut rng).expect("Rare name word lists must not be empty.");
+            self.rare_name = Some(format!("{} {}", first, second));
         }
     }
 
-    pub fn modifiers(&self) -> impl Iterator<Item=(&Modifier, ModifierValue)> {
-        self.implicits.iter().map(|implicit| &**implicit)
-        .chain(self.prefixes.iter().map(|prefix| &**prefix))
-        .chain(self.suffixes.iter().map(|suffix| &**suffix))
-        .flat_map(|affix| affix.modifiers())
+    /// The prefixes and suffixes of the item.
+    pub fn affixes_mut(&mut self) -> impl Iterator<Item=&mut Affix> {
+        self.prefixes.iter_mut().map(DerefMut::deref_mut)
+        .chain(self.suffixes.iter_mut().map(DerefMut::deref_mut))
+    }
+
+    /// The modifiers of the item with the name of where each came from, for explaining stats.
+    ///
+    /// Implicits are named after the base, such as "Stone Tools implicit".
+    /// Socketed runes come after the affixes.
+    pub fn modifiers(&self) -> impl Iterator<Item=(Cow<'_, str>, &Modifier, ModifierValue)> {
+        let implicit_name: Cow<'_, str> = Cow::Owned(format!("{} implicit", self.name));
+
+        let implicits = self.implicits().flat_map(move |implicit| {
+            let implicit_name = implicit_name.clone();
+            implicit.modifiers().map(move |(modifier, value)| (implicit_name.clone(), modifier, value))
+        });
+
+        let affixes = self.prefixes().chain(self.suffixes()).chain(self.runes.iter().map(|rune| &rune.affix))
+        .flat_map(|affix| affix.modifiers().map(|(modifier, value)| (Cow::Borrowed(affix.name.as_str()), modifier, value)));
+
+        implicits.chain(affixes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::{base::Base, definitions::ItemDefinitions, item_database::ItemDatabase, modifier::{AffixId, Prefix}};
+
+    use super::{PushAffixError, Rarity};
+
+    #[test]
+    fn a_second_affix_from_the_same_modifier_group_is_rejected() {
+        let definitions = ItemDefinitions::builtin();
+        let prefix = |id: &str, tier: usize| {
+            let family = definitions.prefixes.iter().find(|family| family.id == AffixId(id.into()))
+            .expect("Shipped item definitions must have the prefix.");
+            Prefix(family.tier_affix(tier))
+        };
+
+        let mut item = ItemDatabase::new().create_test_item(Base::StoneTools);
+        item.increase_quality_to(2);
+
+        item.try_push_prefix(prefix("wood_multiplier", 0)).expect("An empty item must accept a prefix.");
+        assert!(matches!(item.try_push_prefix(prefix("wood_multiplier", 1)), Err(PushAffixError::ModifierGroupAlreadyPresent)));
+        assert_eq!(item.prefixes().count(), 1);
+
+        item.try_push_prefix(prefix("wood_base_gain", 0)).expect("A prefix from another modifier group must be accepted.");
+        assert_eq!(item.prefixes().count(), 2);
+    }
+
+    #[test]
+    fn rarity_comes_from_quality_once_there_are_affixes() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::StoneTools);
+        assert_eq!(item.rarity(), Rarity::Normal);
+
+        item.increase_quality_to(2);
+        assert_eq!(item.rarity(), Rarity::Normal);
+
+        let mut magic = item_db.create_test_item(Base::StoneTools);
+        item_db.try_push_random_prefix(&mut magic).expect("Stone tools must be able to roll a prefix.");
+        assert_eq!(magic.rarity(), Rarity::Magic);
+
+        item_db.try_push_random_prefix(&mut item).expect("Stone tools must be able to roll a prefix.");
+        assert_eq!(item.rarity(), Rarity::Rare);
+
+        // A single prefix still fits in the name.
+        let prefix = item.prefixes().next().expect("The rolled prefix must be on the item.");
+        assert!(item.name().starts_with(prefix.name.as_str()));
     }
 }
@@ -1,14 +1,17 @@
 use std::borrow::Cow;
 
-use bevy::{platform::collections::HashMap, prelude::*};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::incremental::item::affixive_item::{ImplicitIndex, ItemTag};
+use crate::incremental::item::affixive_item::ItemTag;
+use crate::incremental::item::definitions::SetId;
+use crate::incremental::item::modifier::AffixId;
 
 /// A cheap to produce/store tag to access affixive item bases.
 /// 
 /// To access an affixive base, get the `Res<ItemDatabase>` and
 /// call `ItemDatabase.`
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Component, Default, Serialize, Deserialize)]
 pub enum Base {
     #[default] // To allow inclusion in BSN.
     TestTools,
@@ -16,6 +19,22 @@ pub enum Base {
     StoneTools,
 
     WoodenHunt,
+
+    HideWrap,
+    BoneTotem,
+    StoneIdol,
+}
+
+impl Base {
+    pub const LIST: &[Self] = &[
+        Self::TestTools,
+        Self::MakeshiftTools,
+        Self::StoneTools,
+        Self::WoodenHunt,
+        Self::HideWrap,
+        Self::BoneTotem,
+        Self::StoneIdol,
+    ];
 }
 
 impl std::fmt::Display for Base {
@@ -26,6 +45,10 @@ impl std::fmt::Display for Base {
             Base::StoneTools => "Stone Tools",
 
             Base::WoodenHunt => "Wooden Hunting Weapon",
+
+            Base::HideWrap => "Hide Wrap",
+            Base::BoneTotem => "Bone Totem",
+            Base::StoneIdol => "Stone Idol",
         })
     }
 }
@@ -34,47 +57,13 @@ impl std::fmt::Display for Base {
 pub(crate) struct AffixiveItemBase {
     pub name: Cow<'static, str>,
     pub tags: Vec<ItemTag>,
-    pub implicits: Vec<ImplicitIndex>,
-}
+    pub implicits: Vec<AffixId>,
 
-/// Creates the mapping from `Base`s to their `AffxiviteItemBase` data.
-pub fn initialize() -> HashMap<Base, AffixiveItemBase> {
-    let mut map = HashMap::new();
+    /// Maximum durability of items of the base. `None` never wears out.
+    pub durability: Option<f64>,
 
-    map.insert(Base::MakeshiftTools, AffixiveItemBase {
-        name: "Makeshift Tools".into(),
-        tags: vec![ItemTag::Tool],
-        implicits: vec![
-            ImplicitIndex(0),
-            ImplicitIndex(1),
-        ],
-    });
+    pub set: Option<SetId>,
 
-    map.insert(Base::TestTools, AffixiveItemBase {
-        name: "Test Tools".into(),
-        tags: vec![ItemTag::Tool],
-        implicits: vec![
-            ImplicitIndex(2),
-            ImplicitIndex(3),
-        ],
-    });
-
-    map.insert(Base::StoneTools, AffixiveItemBase {
-        name: "Stone Tools".into(),
-        tags: vec![ItemTag::Tool],
-        implicits: vec![
-            ImplicitIndex(2),
-            ImplicitIndex(3),
-        ]
-    });
-
-    map.insert(Base::WoodenHunt, AffixiveItemBase {
-        name: "Wooden Hunting Gear".into(),
-        tags: vec![ItemTag::Hunt],
-        implicits: vec![
-            ImplicitIndex(4),
-        ]
-    });
-
-    map
-}
\ No newline at end of file
+    /// Number of runes that can be socketed into items of the base.
+    pub sockets: u8,
+}
@@ -1,9 +1,10 @@
 //! Crafting information
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use smallvec::{SmallVec, smallvec};
 
-use crate::incremental::{item::item_database::ItemDatabase, log::LogEntry, stock::{StockKind, stockyard::Stockyard}};
+use crate::incremental::{item::{affixive_item::{AffixiveItem, ItemLevel}, definitions::AffixKind, item_database::ItemDatabase, modifier::{AffixId, Prefix, Suffix}}, log::LogEntry, stock::{StockKind, stockyard::Stockyard}};
 
 use super::base::Base;
 
@@ -14,18 +15,56 @@ impl Plugin for ItemCraftPlugin {
         app
         .add_systems(Startup, setup_crafts.in_set(super::super::IncrementalStartupSystemSet))
         .add_observer(on_craft_request)
+        .add_observer(on_bench_craft_request)
         ;
     }
 }
 
 /// Description of how to craft an item.
-#[derive(Debug, Clone, Default, Component)]
+#[derive(Debug, Clone, Default, Component, Serialize, Deserialize)]
 pub struct Recipe {
     // Item base that will be crafted
     pub base: Base,
 
     // Cost in stock resources to craft this item
     pub resources: SmallVec<[(StockKind, f64); 2]>,
+
+    // Item level of the crafted item. Better recipes make items that can roll higher affix tiers.
+    #[serde(default)]
+    pub item_level: ItemLevel,
+}
+
+/// Description of how to add a specific affix to an item at the crafting bench.
+#[derive(Debug, Clone, Default, Component, Serialize, Deserialize)]
+pub struct BenchRecipe {
+    // Whether the affix is a prefix or suffix
+    pub affix_kind: AffixKind,
+
+    // Affix family to add
+    pub affix: AffixId,
+
+    // Tier of the affix family to add, starting at 1
+    pub tier: u8,
+
+    // Cost in stock resources to add the affix
+    pub resources: SmallVec<[(StockKind, f64); 2]>,
+}
+
+impl BenchRecipe {
+    /// Whether the item definitions have the recipe's affix family and tier.
+    pub fn is_defined(&self, item_db: &ItemDatabase) -> bool {
+        item_db.affix_family(self.affix_kind, &self.affix)
+        .is_some_and(|family| self.tier >= 1 && family.tiers.len() >= self.tier as usize)
+    }
+}
+
+/// Event to fire when the player tries to add an affix at the crafting bench.
+#[derive(Debug, Event)]
+pub struct BenchCraftRequest {
+    pub bench_recipe: Entity,
+
+    /// Entity with the [AffixiveItem] to add the affix to.
+    pub item: Entity,
 }
 
 /// Event to fire when the player tries to craft something.
@@ -47,12 +86,14 @@ fn setup_crafts(
     commands.spawn_scene_list(bsn_list!(
         Recipe {
             base: Base::MakeshiftTools,
-            resources: smallvec![(StockKind::BranchesAndPebbles, 1.0)]
+            resources: smallvec![(StockKind::BranchesAndPebbles, 1.0)],
+            item_level: ItemLevel(1),
         },
 
         Recipe {
             base: Base::TestTools,
-            resources: smallvec![]
+            resources: smallvec![],
+            item_level: ItemLevel(30),
         },
     ));
 }
@@ -85,11 +126,63 @@ fn on_craft_request(
         stockyard[stock_kind] -= amount;
     }
 
-    let item = item_db.create_basic(recipe.base);
+    let item = item_db.create_basic(recipe.base, recipe.item_level);
 
     let item_entity = commands.spawn((
         item,
     )).id();
 
     commands.trigger(Crafted { crafted_item: item_entity });
-}
\ No newline at end of file
+}
+
+fn on_bench_craft_request(
+    event: On<BenchCraftRequest>,
+
+    item_db: Res<ItemDatabase>,
+    mut stockyard: ResMut<Stockyard>,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    bench_recipe_query: Query<&BenchRecipe>,
+    mut item_query: Query<&mut AffixiveItem>,
+) {
+    let bench_recipe = bench_recipe_query.get(event.bench_recipe).expect("BenchCraftRequest event's bench recipe entity must have a BenchRecipe component.");
+    let mut item = item_query.get_mut(event.item).expect("BenchCraftRequest event's item entity must have an AffixiveItem component.");
+
+    let has_sufficient_stock = bench_recipe.resources.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount);
+
+    if !has_sufficient_stock {
+        messages.write(format!("Unable to add an affix to '{}'. Insufficient resources.", item.name()).into());
+        return;
+    }
+
+    let Some(affix) = item_db.create_affix_for(&item, bench_recipe.affix_kind, &bench_recipe.affix, bench_recipe.tier) else {
+        messages.write(format!("That {} cannot be added to '{}'.", bench_recipe.affix_kind, item.name()).into());
+        return;
+    };
+
+    let description = affix.display();
+    let name_before = item.name().into_owned();
+
+    let pushed = match bench_recipe.affix_kind {
+        AffixKind::Prefix => item.try_push_prefix(Prefix(affix)),
+        AffixKind::Suffix => item.try_push_suffix(Suffix(affix)),
+        AffixKind::Implicit => unreachable!("Implicits cannot be created for an item."),
+    };
+
+    if let Err(error) = pushed {
+        messages.write(format!("Unable to add an affix to '{}'. {}", item.name(), error).into());
+        return;
+    }
+
+    for &(stock_kind, amount) in bench_recipe.resources.iter() {
+        stockyard[stock_kind] -= amount;
+    }
+
+    let mut message = format!("Added to '{}':\n+ {}", name_before, description);
+    if item.name() != name_before {
+        message.push_str(&format!("\nIt is now '{}'.", item.name()));
+    }
+
+    messages.write(LogEntry(message));
+}
@@ -0,0 +1,338 @@
+//! Crafting currencies: ways to change the affixes of an existing item for a cost in stocks.
+
+use std::fmt::Write as _;
+
+use bevy::prelude::*;
+use rand::Rng as _;
+use smallvec::{SmallVec, smallvec};
+
+use crate::incremental::item::{affixive_item::{AffixiveItem, PushAffixError, Quality}, item_database::ItemDatabase, modifier::Affix};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::{StockKind, stockyard::Stockyard};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum CraftingCurrency {
+    /// Adds one random prefix or suffix. Needs room for it.
+    #[default]
+    AddAffix,
+
+    /// Replaces every prefix and suffix with new random ones, keeping how many there are.
+    RerollAffixes,
+
+    /// Removes one random prefix or suffix.
+    RemoveAffix,
+
+    /// Rerolls the values of every prefix and suffix.
+    RerollValues,
+
+    /// Raises quality by one, making room for another prefix and suffix.
+    UpgradeQuality,
+}
+
+impl CraftingCurrency {
+    pub const LIST: &[Self] = &[
+        Self::AddAffix,
+        Self::RerollAffixes,
+        Self::RemoveAffix,
+        Self::RerollValues,
+        Self::UpgradeQuality,
+    ];
+
+    /// What using the currency does, for showing to the player.
+    pub fn description(self) -> &'static str {
+        match self {
+            CraftingCurrency::AddAffix => "Adds a random prefix or suffix.",
+            CraftingCurrency::RerollAffixes => "Replaces every prefix and suffix with new random ones.",
+            CraftingCurrency::RemoveAffix => "Removes a random prefix or suffix.",
+            CraftingCurrency::RerollValues => "Rerolls the values of every prefix and suffix.",
+            CraftingCurrency::UpgradeQuality => "Makes room for another prefix and suffix.",
+        }
+    }
+
+    /// Stocks spent each time the currency is used.
+    pub fn cost(self) -> SmallVec<[(StockKind, f64); 2]> {
+        match self {
+            CraftingCurrency::AddAffix => smallvec![(StockKind::Stone, 10.0)],
+            CraftingCurrency::RerollAffixes => smallvec![(StockKind::Wood, 20.0), (StockKind::Stone, 20.0)],
+            CraftingCurrency::RemoveAffix => smallvec![(StockKind::Bone, 5.0)],
+            CraftingCurrency::RerollValues => smallvec![(StockKind::Wood, 30.0)],
+            CraftingCurrency::UpgradeQuality => smallvec![(StockKind::Wood, 15.0), (StockKind::Stone, 15.0)],
+        }
+    }
+
+    /// Use the currency on the item.
+    ///
+    /// On success, returns the affixes that were added and removed.
+    /// On failure, the item is unchanged.
+    pub(crate) fn apply(self, item_db: &ItemDatabase, item: &mut AffixiveItem) -> Result<AffixChanges, CurrencyError> {
+        let Quality::Quality(quality) = item.quality() else {
+            return Err(CurrencyError::ItemIsFixed);
+        };
+
+        let mut changes = AffixChanges::default();
+
+        match self {
+            CraftingCurrency::AddAffix => {
+                let added = if rand::rng().random_bool(0.5) {
+                    item_db.try_push_random_prefix(item).or_else(|_| item_db.try_push_random_suffix(item))
+                } else {
+                    item_db.try_push_random_suffix(item).or_else(|_| item_db.try_push_random_prefix(item))
+                };
+
+                changes.added.push(added.map_err(CurrencyError::CannotAddAffix)?);
+            },
+
+            CraftingCurrency::RerollAffixes => {
+                if item.affix_count() == 0 {
+                    return Err(CurrencyError::NoAffixes);
+                }
+
+                // The new affixes are rolled onto a copy, so the item keeps its affixes if any roll fails.
+                let mut rerolled = item.clone();
+                let (prefixes, suffixes) = rerolled.take_affixes();
+
+                // Rolling can only fail if the item definitions changed since the item was made.
+                for _ in prefixes.iter() {
+                    changes.added.push(item_db.try_push_random_prefix(&mut rerolled).map_err(CurrencyError::CannotAddAffix)?);
+                }
+
+                for _ in suffixes.iter() {
+                    changes.added.push(item_db.try_push_random_suffix(&mut rerolled).map_err(CurrencyError::CannotAddAffix)?);
+                }
+
+                *item = rerolled;
+                changes.removed.extend(prefixes.into_iter().map(|prefix| prefix.0));
+                changes.removed.extend(suffixes.into_iter().map(|suffix| suffix.0));
+            },
+
+            CraftingCurrency::RemoveAffix => {
+                if item.affix_count() == 0 {
+                    return Err(CurrencyError::NoAffixes);
+                }
+
+                let index = rand::rng().random_range(0..item.affix_count());
+                changes.removed.push(item.remove_affix(index));
+            },
+
+            CraftingCurrency::RerollValues => {
+                if item.affix_count() == 0 {
+                    return Err(CurrencyError::NoAffixes);
+                }
+
+                for affix in item.affixes_mut() {
+                    changes.removed.push(affix.clone());
+                    affix.randomize_actual();
+                    changes.added.push(affix.clone());
+                }
+            },
+
+            CraftingCurrency::UpgradeQuality => {
+                if quality >= Quality::MAX {
+                    return Err(CurrencyError::QualityAtMaximum);
+                }
+
+                item.increase_quality_to(quality + 1);
+            },
+        }
+
+        Ok(changes)
+    }
+}
+
+impl std::fmt::Display for CraftingCurrency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            CraftingCurrency::AddAffix => "Add Affix",
+            CraftingCurrency::RerollAffixes => "Reroll Affixes",
+            CraftingCurrency::RemoveAffix => "Remove Affix",
+            CraftingCurrency::RerollValues => "Reroll Values",
+            CraftingCurrency::UpgradeQuality => "Upgrade Quality",
+        })
+    }
+}
+
+/// Affixes added to and removed from an item by a crafting currency.
+///
+/// Rerolling an affix's values removes the old affix and adds the rerolled one.
+#[derive(Debug, Default)]
+pub(crate) struct AffixChanges {
+    pub added: Vec<Affix>,
+    pub removed: Vec<Affix>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum CurrencyError {
+    ItemIsFixed,
+    NoAffixes,
+    QualityAtMaximum,
+    CannotAddAffix(PushAffixError),
+}
+
+impl std::fmt::Display for CurrencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            CurrencyError::ItemIsFixed => f.write_str("You cannot modify the affixes of this."),
+            CurrencyError::NoAffixes => f.write_str("The item has no affixes."),
+            CurrencyError::QualityAtMaximum => f.write_str("The item's quality is already at its maximum."),
+            CurrencyError::CannotAddAffix(error) => write!(f, "{}", error),
+        }
+    }
+}
+
+/// Event to fire when the player uses a crafting currency on an item.
+#[derive(Debug, Event)]
+pub struct UseCurrencyRequest {
+    /// Entity with the [AffixiveItem] to modify.
+    pub item: Entity,
+    pub currency: CraftingCurrency,
+}
+
+/// Event for when a crafting currency changed an item.
+#[derive(Debug, Event)]
+pub struct CraftOutcome {
+    pub item: Entity,
+    pub currency: CraftingCurrency,
+
+    pub added: Vec<Affix>,
+    pub removed: Vec<Affix>,
+
+    pub quality_before: Quality,
+    pub quality_after: Quality,
+}
+
+pub(super) fn on_use_currency_request(
+    event: On<UseCurrencyRequest>,
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+    mut stockyard: ResMut<Stockyard>,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_query: Query<&mut AffixiveItem>,
+) {
+    let currency = event.currency;
+    let mut item = item_query.get_mut(event.item).expect("UseCurrencyRequest event's item entity must have an AffixiveItem component.");
+
+    let cost = currency.cost();
+    let has_sufficient_stock = cost.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount);
+
+    if !has_sufficient_stock {
+        messages.write(format!("Unable to use {}. Insufficient resources.", currency).into());
+        return;
+    }
+
+    let quality_before = item.quality();
+    let name_before = item.name().into_owned();
+
+    let changes = match currency.apply(&item_db, &mut item) {
+        Ok(changes) => changes,
+        Err(error) => {
+            messages.write(format!("Unable to use {} on '{}'. {}", currency, name_before, error).into());
+            return;
+        }
+    };
+
+    for &(stock_kind, amount) in cost.iter() {
+        stockyard[stock_kind] -= amount;
+    }
+
+    let mut message = format!("Used {} on '{}'.", currency, name_before);
+
+    for affix in changes.removed.iter() {
+        let _ = write!(message, "\n- {}", affix.display());
+    }
+
+    for affix in changes.added.iter() {
+        let _ = write!(message, "\n+ {}", affix.display());
+    }
+
+    if let Quality::Quality(quality) = item.quality() && item.quality() != quality_before {
+        let _ = write!(message, "\nQuality is now {}.", quality);
+    }
+
+    if item.name() != name_before {
+        let _ = write!(message, "\nIt is now '{}'.", item.name());
+    }
+
+    messages.write(LogEntry(message));
+
+    commands.trigger(CraftOutcome {
+        item: event.item,
+        currency,
+        added: changes.added,
+        removed: changes.removed,
+        quality_before,
+        quality_after: item.quality(),
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::{affixive_item::{AffixiveItem, PushAffixError, Quality}, base::Base, definitions::{ArtifactId, ItemDefinitions}, item_database::ItemDatabase, modifier::AffixId};
+    use crate::incremental::story::REMEMBERED_ARTIFACT;
+
+    use super::{CraftingCurrency, CurrencyError};
+
+    fn artifact(item_db: &ItemDatabase) -> AffixiveItem {
+        item_db.create_artifact(&ArtifactId(REMEMBERED_ARTIFACT.into()))
+        .expect("Shipped item definitions must have the story's artifact.")
+    }
+
+    #[test]
+    fn currencies_reject_artifacts() {
+        let item_db = ItemDatabase::new();
+
+        for currency in CraftingCurrency::LIST.iter().copied() {
+            let mut item = artifact(&item_db);
+            let affix_count = item.affix_count();
+
+            let result = currency.apply(&item_db, &mut item);
+
+            assert!(matches!(result, Err(CurrencyError::ItemIsFixed)), "{} must reject artifacts.", currency);
+            assert_eq!(item.affix_count(), affix_count);
+            assert_eq!(item.quality(), Quality::FixedArtifact);
+        }
+    }
+
+    #[test]
+    fn random_affixes_cannot_be_pushed_onto_artifacts() {
+        let item_db = ItemDatabase::new();
+        let mut item = artifact(&item_db);
+
+        assert!(matches!(item_db.try_push_random_prefix(&mut item), Err(PushAffixError::AffixiveItemIsFixed)));
+        assert!(matches!(item_db.try_push_random_suffix(&mut item), Err(PushAffixError::AffixiveItemIsFixed)));
+    }
+
+    #[test]
+    fn failed_rerolls_keep_every_affix() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::MakeshiftTools);
+        item_db.try_push_random_prefix(&mut item).expect("Makeshift tools must be able to roll a prefix.");
+        item_db.try_push_random_suffix(&mut item).expect("Makeshift tools must be able to roll a suffix.");
+
+        // Definitions that changed since the item was made, so the only suffix shares the group of the only prefix.
+        let mut definitions = ItemDefinitions::builtin();
+        let prefix = definitions.prefixes.iter().find(|family| family.id == AffixId("wood_base_gain".into()))
+        .expect("Shipped item definitions must have the prefix.").clone();
+        let suffix = definitions.suffixes.iter_mut().find(|family| family.id == AffixId("wood_affinity_chance".into()))
+        .expect("Shipped item definitions must have the suffix.");
+        suffix.group = prefix.group.clone();
+        let suffix_id = suffix.id.clone();
+
+        for pool in definitions.affix_pools.iter_mut().filter(|pool| pool.base == Base::MakeshiftTools) {
+            pool.prefixes = vec![prefix.id.clone()];
+            pool.suffixes = vec![suffix_id.clone()];
+        }
+
+        let changed_db = ItemDatabase::from_definitions(&definitions).expect("Changed item definitions must still be valid.");
+
+        let affix_names = |item: &AffixiveItem| item.prefixes().chain(item.suffixes()).map(|affix| affix.name.clone()).collect::<Vec<_>>();
+        let before = affix_names(&item);
+
+        let result = CraftingCurrency::RerollAffixes.apply(&changed_db, &mut item);
+
+        assert!(matches!(result, Err(CurrencyError::CannotAddAffix(PushAffixError::NoEligibleAffix))));
+        assert_eq!(affix_names(&item), before);
+    }
+}
@@ -0,0 +1,753 @@
+//! Item content loaded from `assets/items.ron`.
+//!
+//! The file is compiled in so that an [ItemDatabase] is available immediately,
+//! then loaded again through the asset server. Whenever the asset finishes loading
+//! or is changed on disk, the [ItemDatabase] is rebuilt from it.
+//!
+//! Definitions are validated before a database is built from them. Broken
+//! built in definitions panic at startup; broken reloaded definitions are
+//! logged and the previous database is kept.
+
+use bevy::ecs::error::BevyError;
+use bevy::asset::{AssetLoader, LoadContext, io::Reader};
+use bevy::platform::collections::HashSet;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::incremental::item::affixive_item::{ItemLevel, ItemTag};
+use crate::incremental::item::base::Base;
+use crate::incremental::item::item_slot::ItemSlotId;
+use crate::incremental::item::item_database::ItemDatabase;
+use crate::incremental::item::modifier::{Affix, AffixId, Modifier, ModifierGroup, ModifierKind, ModifierValue};
+use crate::incremental::stock::StockKind;
+
+/// The item definitions shipped with the game.
+pub(crate) const BUILTIN_ITEM_DEFINITIONS: &str = include_str!("../../../assets/items.ron");
+
+const ITEM_DEFINITIONS_PATH: &str = "items.ron";
+
+#[derive(Debug, Clone, Asset, TypePath, Deserialize)]
+pub struct ItemDefinitions {
+    pub bases: Vec<BaseDefinition>,
+    pub implicits: Vec<AffixDefinition>,
+    pub prefixes: Vec<AffixFamilyDefinition>,
+    pub suffixes: Vec<AffixFamilyDefinition>,
+    pub affix_pools: Vec<AffixPoolDefinition>,
+
+    #[serde(default)]
+    pub artifacts: Vec<ArtifactDefinition>,
+
+    #[serde(default)]
+    pub sets: Vec<SetDefinition>,
+
+    #[serde(default)]
+    pub runes: Vec<RuneDefinition>,
+
+    /// In the order they are shown in the inventory.
+    pub slots: Vec<SlotDefinition>,
+}
+
+/// An item slot and which items can be put into it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SlotDefinition {
+    pub id: ItemSlotId,
+    pub name: String,
+
+    /// Items with any of these tags can be put into the slot.
+    pub accepts: SmallVec<[ItemTag; 2]>,
+
+    /// Locked slots are unlocked by the story.
+    #[serde(default)]
+    pub locked: bool,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BaseDefinition {
+    pub base: Base,
+    pub name: String,
+    pub tags: Vec<ItemTag>,
+    pub implicits: Vec<AffixId>,
+
+    /// Seconds of use before items of the base break. Bases without it never wear out.
+    #[serde(default)]
+    pub durability: Option<f64>,
+
+    /// Set that items of the base are a piece of.
+    #[serde(default)]
+    pub set: Option<SetId>,
+
+    /// Number of runes that can be socketed into items of the base.
+    #[serde(default)]
+    pub sockets: u8,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffixDefinition {
+    pub id: AffixId,
+    pub name: String,
+    pub modifier: Modifier,
+}
+
+/// A prefix or suffix that comes in tiers of increasing strength.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffixFamilyDefinition {
+    pub id: AffixId,
+    pub name: String,
+    pub group: ModifierGroup,
+    pub kind: ModifierKind,
+
+    /// Kind of the second modifier of a hybrid affix. Every tier then needs a `hybrid` range.
+    #[serde(default)]
+    pub hybrid_kind: Option<ModifierKind>,
+
+    /// Ordered from weakest to strongest.
+    pub tiers: Vec<AffixTierDefinition>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffixTierDefinition {
+    /// Minimum item level for this tier to roll.
+    pub level: ItemLevel,
+
+    /// Relative chance of this tier rolling compared to the other tiers that can roll.
+    pub weight: u32,
+
+    pub min: ModifierValue,
+    pub max: ModifierValue,
+
+    /// Range of the second modifier of a hybrid affix.
+    #[serde(default)]
+    pub hybrid: Option<ModifierRange>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct ModifierRange {
+    pub min: ModifierValue,
+    pub max: ModifierValue,
+}
+
+impl AffixFamilyDefinition {
+    /// Construct an affix of the tier at the index with an unrolled value.
+    pub fn tier_affix(&self, index: usize) -> Affix {
+        let tier = &self.tiers[index];
+        let modifier = Modifier { kind: self.kind, min: tier.min, max: tier.max };
+
+        let hybrid_modifier = self.hybrid_kind.zip(tier.hybrid)
+        .map(|(kind, range)| Modifier { kind, min: range.min, max: range.max });
+
+        Affix {
+            hybrid_modifier,
+            tier: Some(index as u8 + 1),
+            group: Some(self.group.clone()),
+            ..Affix::new(self.name.clone(), modifier)
+        }
+    }
+
+    /// Whether any tier of this family can roll at the item level.
+    fn can_roll_at(&self, item_level: ItemLevel) -> bool {
+        self.tiers.iter().any(|tier| tier.level <= item_level && tier.weight > 0)
+    }
+}
+
+/// The prefixes and suffixes that can roll on items of a base.
+#[derive(Debug, Clone, Deserialize)]
+pub struct AffixPoolDefinition {
+    pub base: Base,
+    pub prefixes: Vec<AffixId>,
+    pub suffixes: Vec<AffixId>,
+}
+
+/// Stable identifier of an artifact in the item definitions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ArtifactId(pub String);
+
+impl std::fmt::Display for ArtifactId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A named item with predetermined affixes that cannot be changed.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactDefinition {
+    pub id: ArtifactId,
+    pub name: String,
+    pub base: Base,
+
+    /// Replaces the base's implicits.
+    pub implicits: Vec<ArtifactAffixDefinition>,
+    #[serde(default)]
+    pub prefixes: Vec<ArtifactAffixDefinition>,
+    #[serde(default)]
+    pub suffixes: Vec<ArtifactAffixDefinition>,
+
+    /// Set the artifact is a piece of. Artifacts are not part of their base's set.
+    #[serde(default)]
+    pub set: Option<SetId>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ArtifactAffixDefinition {
+    pub name: String,
+    pub modifier: Modifier,
+
+    /// Second modifier of a hybrid affix.
+    #[serde(default)]
+    pub hybrid: Option<Modifier>,
+}
+
+impl ArtifactAffixDefinition {
+    /// Construct the affix with its values rolled.
+    pub fn rolled_affix(&self) -> Affix {
+        let mut affix = Affix {
+            hybrid_modifier: self.hybrid,
+            ..Affix::new(self.name.clone(), self.modifier)
+        };
+        affix.randomize_actual();
+        affix
+    }
+
+    fn modifiers(&self) -> impl Iterator<Item=&Modifier> {
+        std::iter::once(&self.modifier).chain(self.hybrid.as_ref())
+    }
+}
+
+/// Stable identifier of an item set in the item definitions.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct SetId(pub String);
+
+impl std::fmt::Display for SetId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Bases and artifacts sharing the set's id are its pieces.
+/// Equipping different pieces together grants the set's bonuses.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetDefinition {
+    pub id: SetId,
+    pub name: String,
+
+    /// Ordered from fewest to most pieces.
+    pub bonuses: Vec<SetBonusDefinition>,
+}
+
+/// A modifier granted while enough different pieces of the set are equipped.
+#[derive(Debug, Clone, Deserialize)]
+pub struct SetBonusDefinition {
+    pub pieces: usize,
+    pub kind: ModifierKind,
+    pub value: ModifierValue,
+}
+
+impl SetDefinition {
+    /// The bonuses granted with the number of different pieces equipped.
+    pub fn active_bonuses(&self, pieces: usize) -> impl Iterator<Item=&SetBonusDefinition> {
+        self.bonuses.iter().filter(move |bonus| bonus.pieces <= pieces)
+    }
+
+    /// Name of a bonus for explaining stats, such as "Hunter's Kit (2)".
+    pub fn bonus_name(&self, bonus: &SetBonusDefinition) -> String {
+        format!("{} ({})", self.name, bonus.pieces)
+    }
+}
+
+impl SetBonusDefinition {
+    pub fn display(&self) -> String {
+        format!("({}) {}", self.pieces, self.kind.display_actual(self.value))
+    }
+}
+
+/// Stable identifier of a rune in the item definitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct RuneId(pub String);
+
+impl std::fmt::Display for RuneId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// A rune with a single modifier, crafted from stocks and socketed into items.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RuneDefinition {
+    pub id: RuneId,
+    pub name: String,
+    pub modifier: Modifier,
+
+    /// Cost in stock resources to craft the rune.
+    pub resources: SmallVec<[(StockKind, f64); 2]>,
+}
+
+impl ItemDefinitions {
+    pub fn builtin() -> Self {
+        ron::from_str(BUILTIN_ITEM_DEFINITIONS).expect("Built in item definitions must be valid RON.")
+    }
+
+    /// Check the definitions for every problem that would otherwise panic or
+    /// silently misbehave once items are created from them.
+    pub fn validate(&self) -> Result<(), Vec<ItemDefinitionError>> {
+        let mut errors = vec![];
+
+        let implicit_ids = validate_implicits(&self.implicits, &mut errors);
+        let prefix_ids = validate_families(&self.prefixes, AffixKind::Prefix, &mut errors);
+        let suffix_ids = validate_families(&self.suffixes, AffixKind::Suffix, &mut errors);
+
+        let mut defined_bases = HashSet::new();
+        for definition in self.bases.iter() {
+            if !defined_bases.insert(definition.base) {
+                errors.push(ItemDefinitionError::DuplicateBase(definition.base));
+            }
+
+            validate_references(definition.base, AffixKind::Implicit, &definition.implicits, &implicit_ids, &mut errors);
+
+            if let Some(durability) = definition.durability && durability <= 0.0 {
+                errors.push(ItemDefinitionError::NonPositiveDurability(definition.base));
+            }
+        }
+
+        let mut pooled_bases = HashSet::new();
+        for pool in self.affix_pools.iter() {
+            if !pooled_bases.insert(pool.base) {
+                errors.push(ItemDefinitionError::DuplicateAffixPool(pool.base));
+            }
+
+            for (kind, ids, families, known_ids) in [
+                (AffixKind::Prefix, &pool.prefixes, &self.prefixes, &prefix_ids),
+                (AffixKind::Suffix, &pool.suffixes, &self.suffixes, &suffix_ids),
+            ] {
+                // Every item must be able to roll an affix, even at the lowest item level.
+                let can_roll = families.iter()
+                .any(|family| ids.contains(&family.id) && family.can_roll_at(ItemLevel::MIN));
+
+                if !can_roll {
+                    errors.push(ItemDefinitionError::EmptyAffixPool { base: pool.base, kind });
+                }
+
+                validate_references(pool.base, kind, ids, known_ids, &mut errors);
+            }
+        }
+
+        let mut artifact_ids = HashSet::new();
+        for artifact in self.artifacts.iter() {
+            if !artifact_ids.insert(&artifact.id) {
+                errors.push(ItemDefinitionError::DuplicateArtifactId(artifact.id.clone()));
+            }
+
+            let affixes = artifact.implicits.iter().chain(artifact.prefixes.iter()).chain(artifact.suffixes.iter());
+            for modifier in affixes.flat_map(ArtifactAffixDefinition::modifiers) {
+                if modifier.min > modifier.max {
+                    errors.push(ItemDefinitionError::InvertedArtifactModifierRange { id: artifact.id.clone(), min: modifier.min, max: modifier.max });
+                }
+            }
+        }
+
+        let mut set_ids = HashSet::new();
+        for set in self.sets.iter() {
+            if !set_ids.insert(&set.id) {
+                errors.push(ItemDefinitionError::DuplicateSetId(set.id.clone()));
+            }
+
+            for bonus in set.bonuses.iter() {
+                if bonus.pieces < 2 {
+                    errors.push(ItemDefinitionError::SetBonusTooFewPieces { id: set.id.clone(), pieces: bonus.pieces });
+                }
+            }
+        }
+
+        let set_references = self.bases.iter().map(|definition| (definition.name.as_str(), &definition.set))
+        .chain(self.artifacts.iter().map(|artifact| (artifact.name.as_str(), &artifact.set)));
+
+        for (piece, set) in set_references {
+            if let Some(id) = set && !set_ids.contains(id) {
+                errors.push(ItemDefinitionError::DanglingSetId { piece: piece.to_string(), id: id.clone() });
+            }
+        }
+
+        let mut rune_ids = HashSet::new();
+        for rune in self.runes.iter() {
+            if !rune_ids.insert(&rune.id) {
+                errors.push(ItemDefinitionError::DuplicateRuneId(rune.id.clone()));
+            }
+
+            if rune.modifier.min > rune.modifier.max {
+                errors.push(ItemDefinitionError::InvertedRuneModifierRange { id: rune.id.clone(), min: rune.modifier.min, max: rune.modifier.max });
+            }
+
+            if rune.resources.iter().all(|&(_, amount)| amount <= 0.0) {
+                errors.push(ItemDefinitionError::FreeRune(rune.id.clone()));
+            }
+        }
+
+        let mut defined_slots = HashSet::new();
+        for definition in self.slots.iter() {
+            if !defined_slots.insert(&definition.id) {
+                errors.push(ItemDefinitionError::DuplicateSlot(definition.id.clone()));
+            }
+
+            if definition.accepts.is_empty() {
+                errors.push(ItemDefinitionError::SlotAcceptsNothing(definition.id.clone()));
+            }
+        }
+
+        for base in Base::LIST.iter().copied() {
+            if !defined_bases.contains(&base) {
+                errors.push(ItemDefinitionError::MissingBase(base));
+            }
+
+            if !pooled_bases.contains(&base) {
+                errors.push(ItemDefinitionError::MissingAffixPool(base));
+            }
+        }
+
+        if errors.is_empty() { Ok(()) } else { Err(errors) }
+    }
+}
+
+/// Checks each implicit for duplicate ids and broken modifier ranges, returning the ids seen.
+fn validate_implicits<'a>(definitions: &'a [AffixDefinition], errors: &mut Vec<ItemDefinitionError>) -> HashSet<&'a AffixId> {
+    let kind = AffixKind::Implicit;
+    let mut ids = HashSet::new();
+
+    for definition in definitions.iter() {
+        if !ids.insert(&definition.id) {
+            errors.push(ItemDefinitionError::DuplicateAffixId { kind, id: definition.id.clone() });
+        }
+
+        let Modifier { min, max, .. } = definition.modifier;
+        if min > max {
+            errors.push(ItemDefinitionError::InvertedModifierRange { kind, id: definition.id.clone(), tier: None, min, max });
+        }
+    }
+
+    ids
+}
+
+/// Checks each affix family for duplicate ids, missing or unordered tiers and broken modifier ranges, returning the ids seen.
+fn validate_families<'a>(definitions: &'a [AffixFamilyDefinition], kind: AffixKind, errors: &mut Vec<ItemDefinitionError>) -> HashSet<&'a AffixId> {
+    let mut ids = HashSet::new();
+
+    for definition in definitions.iter() {
+        if !ids.insert(&definition.id) {
+            errors.push(ItemDefinitionError::DuplicateAffixId { kind, id: definition.id.clone() });
+        }
+
+        if definition.tiers.is_empty() {
+            errors.push(ItemDefinitionError::NoTiers { kind, id: definition.id.clone() });
+        }
+
+        for (index, tier) in definition.tiers.iter().enumerate() {
+            let tier_number = Some(index as u8 + 1);
+
+            if tier.min > tier.max {
+                errors.push(ItemDefinitionError::InvertedModifierRange { kind, id: definition.id.clone(), tier: tier_number, min: tier.min, max: tier.max });
+            }
+
+            if index > 0 && tier.level < definition.tiers[index - 1].level {
+                errors.push(ItemDefinitionError::UnorderedTierLevel { kind, id: definition.id.clone(), tier: index as u8 + 1 });
+            }
+
+            if definition.hybrid_kind.is_some() != tier.hybrid.is_some() {
+                errors.push(ItemDefinitionError::MismatchedHybrid { kind, id: definition.id.clone(), tier: index as u8 + 1 });
+            }
+
+            if let Some(hybrid) = tier.hybrid && hybrid.min > hybrid.max {
+                errors.push(ItemDefinitionError::InvertedModifierRange { kind, id: definition.id.clone(), tier: tier_number, min: hybrid.min, max: hybrid.max });
+            }
+        }
+    }
+
+    ids
+}
+
+fn validate_references(base: Base, kind: AffixKind, ids: &[AffixId], known_ids: &HashSet<&AffixId>, errors: &mut Vec<ItemDefinitionError>) {
+    for id in ids.iter() {
+        if !known_ids.contains(id) {
+            errors.push(ItemDefinitionError::DanglingAffixId { base, kind, id: id.clone() });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum AffixKind {
+    Implicit,
+    #[default]
+    Prefix,
+    Suffix,
+}
+
+impl std::fmt::Display for AffixKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            AffixKind::Implicit => "implicit",
+            AffixKind::Prefix => "prefix",
+            AffixKind::Suffix => "suffix",
+        })
+    }
+}
+
+/// A problem found while validating [ItemDefinitions].
+#[derive(Debug, Clone, PartialEq)]
+pub enum ItemDefinitionError {
+    /// The base is in the game but has no definition.
+    MissingBase(Base),
+    DuplicateBase(Base),
+
+    /// Items of the base would be broken as soon as they are made.
+    NonPositiveDurability(Base),
+
+    /// The base is in the game but has no affix pool, so affixes cannot roll on it.
+    MissingAffixPool(Base),
+    DuplicateAffixPool(Base),
+
+    /// None of the pool's affixes of the kind can roll at [ItemLevel::MIN].
+    EmptyAffixPool { base: Base, kind: AffixKind },
+
+    DuplicateAffixId { kind: AffixKind, id: AffixId },
+    NoTiers { kind: AffixKind, id: AffixId },
+
+    /// The tier rolls at a lower item level than the tier before it, so tiers would not get better as items level up.
+    UnorderedTierLevel { kind: AffixKind, id: AffixId, tier: u8 },
+
+    /// A tier has a `hybrid` range but its family has no `hybrid_kind`, or the other way around.
+    MismatchedHybrid { kind: AffixKind, id: AffixId, tier: u8 },
+
+    /// The modifier's `min` is greater than its `max`, so there is no value to roll.
+    InvertedModifierRange { kind: AffixKind, id: AffixId, tier: Option<u8>, min: ModifierValue, max: ModifierValue },
+
+    /// A base definition or affix pool refers to an affix that is not defined.
+    DanglingAffixId { base: Base, kind: AffixKind, id: AffixId },
+
+    DuplicateArtifactId(ArtifactId),
+    InvertedArtifactModifierRange { id: ArtifactId, min: ModifierValue, max: ModifierValue },
+
+    DuplicateSetId(SetId),
+
+    /// A set bonus for fewer than two pieces would be granted by a single item.
+    SetBonusTooFewPieces { id: SetId, pieces: usize },
+
+    /// A base or artifact, named by `piece`, is a piece of a set that is not defined.
+    DanglingSetId { piece: String, id: SetId },
+
+    DuplicateRuneId(RuneId),
+    InvertedRuneModifierRange { id: RuneId, min: ModifierValue, max: ModifierValue },
+
+    /// The rune could be crafted endlessly for nothing.
+    FreeRune(RuneId),
+
+    DuplicateSlot(ItemSlotId),
+    SlotAcceptsNothing(ItemSlotId),
+}
+
+impl std::fmt::Display for ItemDefinitionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemDefinitionError::MissingBase(base) => write!(f, "{} has no base definition", base),
+            ItemDefinitionError::DuplicateBase(base) => write!(f, "{} is defined more than once", base),
+            ItemDefinitionError::NonPositiveDurability(base) => write!(f, "{} must have a durability above zero", base),
+            ItemDefinitionError::MissingAffixPool(base) => write!(f, "{} has no affix pool", base),
+            ItemDefinitionError::DuplicateAffixPool(base) => write!(f, "{} has more than one affix pool", base),
+            ItemDefinitionError::EmptyAffixPool { base, kind } => write!(f, "{} has no {}es in its affix pool that can roll at item level {}", base, kind, ItemLevel::MIN),
+            ItemDefinitionError::DuplicateAffixId { kind, id } => write!(f, "{} `{}` is defined more than once", kind, id),
+            ItemDefinitionError::NoTiers { kind, id } => write!(f, "{} `{}` has no tiers", kind, id),
+            ItemDefinitionError::UnorderedTierLevel { kind, id, tier } => write!(f, "{} `{}` tier {} has a lower level than tier {}", kind, id, tier, tier - 1),
+            ItemDefinitionError::MismatchedHybrid { kind, id, tier } => write!(f, "{} `{}` tier {} must have a hybrid range exactly when the {} has a hybrid kind", kind, id, tier, kind),
+            ItemDefinitionError::InvertedModifierRange { kind, id, tier: None, min, max } => write!(f, "{} `{}` has a minimum of {} above its maximum of {}", kind, id, min, max),
+            ItemDefinitionError::InvertedModifierRange { kind, id, tier: Some(tier), min, max } => write!(f, "{} `{}` tier {} has a minimum of {} above its maximum of {}", kind, id, tier, min, max),
+            ItemDefinitionError::DanglingAffixId { base, kind, id } => write!(f, "{} refers to {} `{}` which is not defined", base, kind, id),
+            ItemDefinitionError::DuplicateArtifactId(id) => write!(f, "artifact `{}` is defined more than once", id),
+            ItemDefinitionError::InvertedArtifactModifierRange { id, min, max } => write!(f, "artifact `{}` has a modifier with a minimum of {} above its maximum of {}", id, min, max),
+            ItemDefinitionError::DuplicateSetId(id) => write!(f, "set `{}` is defined more than once", id),
+            ItemDefinitionError::SetBonusTooFewPieces { id, pieces } => write!(f, "set `{}` has a bonus for {} pieces, but bonuses need at least 2", id, pieces),
+            ItemDefinitionError::DanglingSetId { piece, id } => write!(f, "{} is a piece of set `{}` which is not defined", piece, id),
+            ItemDefinitionError::DuplicateRuneId(id) => write!(f, "rune `{}` is defined more than once", id),
+            ItemDefinitionError::InvertedRuneModifierRange { id, min, max } => write!(f, "rune `{}` has a minimum of {} above its maximum of {}", id, min, max),
+            ItemDefinitionError::FreeRune(id) => write!(f, "rune `{}` costs nothing to craft", id),
+            ItemDefinitionError::DuplicateSlot(slot) => write!(f, "item slot `{}` is defined more than once", slot),
+            ItemDefinitionError::SlotAcceptsNothing(slot) => write!(f, "item slot `{}` accepts no item tags", slot),
+        }
+    }
+}
+
+/// Join validation errors into one message, one error per line.
+pub fn display_errors(errors: &[ItemDefinitionError]) -> String {
+    errors.iter().map(|error| error.to_string()).collect::<Vec<_>>().join("\n")
+}
+
+#[derive(Debug, Default, TypePath)]
+pub(super) struct ItemDefinitionsLoader;
+
+impl AssetLoader for ItemDefinitionsLoader {
+    type Asset = ItemDefinitions;
+    type Settings = ();
+    type Error = BevyError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        Ok(ron::de::from_bytes(&bytes)?)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["ron"]
+    }
+}
+
+/// Keeps the loaded item definitions alive so they can be hot-reloaded.
+#[derive(Debug, Resource)]
+pub(super) struct ItemDefinitionsHandle(#[expect(unused, reason = "Only held to keep the asset loaded.")] Handle<ItemDefinitions>);
+
+pub(super) fn load_item_definitions(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(ItemDefinitionsHandle(asset_server.load(ITEM_DEFINITIONS_PATH)));
+}
+
+pub(super) fn rebuild_item_database(
+    mut asset_events: MessageReader<AssetEvent<ItemDefinitions>>,
+    item_definitions: Res<Assets<ItemDefinitions>>,
+    mut item_db: ResMut<ItemDatabase>,
+) {
+    for event in asset_events.read() {
+        let (AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id }) = event else { continue; };
+        let Some(definitions) = item_definitions.get(*id) else { continue; };
+
+        match ItemDatabase::from_definitions(definitions) {
+            Ok(new_item_db) => {
+                *item_db = new_item_db;
+                info!("Rebuilt the item database from {}.", ITEM_DEFINITIONS_PATH);
+            },
+
+            Err(errors) => {
+                error!("Kept the previous item database because {} is invalid:\n{}", ITEM_DEFINITIONS_PATH, display_errors(&errors));
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::modifier::AffixId;
+
+    use super::{AffixKind, ItemDefinitionError, ItemDefinitions, SetId, display_errors};
+
+    #[test]
+    fn hybrid_affixes_roll_both_modifiers() {
+        let definitions = ItemDefinitions::builtin();
+        let family = definitions.prefixes.iter().find(|family| family.hybrid_kind.is_some())
+        .expect("Shipped item definitions must have a hybrid prefix.");
+
+        for (index, tier) in family.tiers.iter().enumerate() {
+            let mut affix = family.tier_affix(index);
+            affix.randomize_actual();
+
+            let hybrid = tier.hybrid.expect("Tiers of hybrid families must have a hybrid range.");
+            let rolled: Vec<_> = affix.modifiers().map(|(modifier, value)| (modifier.kind, value)).collect();
+
+            assert_eq!(rolled.len(), 2);
+            assert_eq!(rolled[0].0, family.kind);
+            assert!((tier.min..=tier.max).contains(&rolled[0].1));
+            assert_eq!(Some(rolled[1].0), family.hybrid_kind);
+            assert!((hybrid.min..=hybrid.max).contains(&rolled[1].1));
+        }
+    }
+
+    #[test]
+    fn shipped_item_definitions_are_valid() {
+        if let Err(errors) = ItemDefinitions::builtin().validate() {
+            panic!("assets/items.ron is invalid:\n{}", display_errors(&errors));
+        }
+    }
+
+    #[test]
+    fn broken_item_definitions_report_every_error() {
+        let mut definitions = ItemDefinitions::builtin();
+
+        definitions.affix_pools.retain(|pool| pool.base != Base::WoodenHunt);
+        definitions.bases[0].implicits.push(AffixId("missing".into()));
+        definitions.prefixes[0].tiers[0].min = definitions.prefixes[0].tiers[0].max + 1;
+        definitions.suffixes.push(definitions.suffixes[0].clone());
+
+        let errors = definitions.validate().expect_err("Broken definitions must not validate.");
+
+        assert!(errors.contains(&ItemDefinitionError::MissingAffixPool(Base::WoodenHunt)));
+        assert!(errors.contains(&ItemDefinitionError::DanglingAffixId { base: definitions.bases[0].base, kind: AffixKind::Implicit, id: AffixId("missing".into()) }));
+        assert!(errors.iter().any(|error| matches!(error, ItemDefinitionError::InvertedModifierRange { kind: AffixKind::Prefix, tier: Some(1), .. })));
+        assert!(errors.contains(&ItemDefinitionError::DuplicateAffixId { kind: AffixKind::Suffix, id: definitions.suffixes[0].id.clone() }));
+        assert_eq!(errors.len(), 4);
+    }
+
+    #[test]
+    fn tiers_out_of_level_order_are_reported() {
+        let mut definitions = ItemDefinitions::builtin();
+        let family = &mut definitions.prefixes[0];
+        assert!(family.tiers[1].level < family.tiers[2].level, "The shipped prefix's tiers must have increasing levels.");
+
+        family.tiers.swap(1, 2);
+        let id = family.id.clone();
+
+        let errors = definitions.validate().expect_err("Tiers out of level order must not validate.");
+
+        assert_eq!(errors, [ItemDefinitionError::UnorderedTierLevel { kind: AffixKind::Prefix, id, tier: 3 }]);
+    }
+
+    #[test]
+    fn broken_slot_definitions_are_reported() {
+        let mut definitions = ItemDefinitions::builtin();
+
+        definitions.slots[0].accepts.clear();
+        definitions.slots.push(definitions.slots[1].clone());
+
+        let errors = definitions.validate().expect_err("Broken slot definitions must not validate.");
+
+        assert!(errors.contains(&ItemDefinitionError::SlotAcceptsNothing(definitions.slots[0].id.clone())));
+        assert!(errors.contains(&ItemDefinitionError::DuplicateSlot(definitions.slots[1].id.clone())));
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    fn broken_set_definitions_are_reported() {
+        let mut definitions = ItemDefinitions::builtin();
+
+        let set = definitions.sets.first().expect("Shipped item definitions must have a set.").clone();
+        definitions.sets[0].bonuses[0].pieces = 1;
+        definitions.sets.push(set.clone());
+        definitions.bases[0].set = Some(SetId("missing".into()));
+
+        let errors = definitions.validate().expect_err("Broken set definitions must not validate.");
+
+        assert!(errors.contains(&ItemDefinitionError::SetBonusTooFewPieces { id: set.id.clone(), pieces: 1 }));
+        assert!(errors.contains(&ItemDefinitionError::DuplicateSetId(set.id.clone())));
+        assert!(errors.contains(&ItemDefinitionError::DanglingSetId { piece: definitions.bases[0].name.clone(), id: SetId("missing".into()) }));
+        assert_eq!(errors.len(), 3);
+    }
+
+    #[test]
+    fn broken_rune_definitions_are_reported() {
+        let mut definitions = ItemDefinitions::builtin();
+
+        let rune = definitions.runes.first().expect("Shipped item definitions must have a rune.").clone();
+        definitions.runes[0].resources.clear();
+        definitions.runes[0].modifier.min = definitions.runes[0].modifier.max + 1;
+        definitions.runes.push(rune.clone());
+
+        let errors = definitions.validate().expect_err("Broken rune definitions must not validate.");
+
+        assert!(errors.contains(&ItemDefinitionError::FreeRune(rune.id.clone())));
+        assert!(errors.iter().any(|error| matches!(error, ItemDefinitionError::InvertedRuneModifierRange { id, .. } if *id == rune.id)));
+        assert!(errors.contains(&ItemDefinitionError::DuplicateRuneId(rune.id.clone())));
+        assert_eq!(errors.len(), 3);
+    }
+}
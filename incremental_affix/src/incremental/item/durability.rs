@@ -0,0 +1,257 @@
+//! Equipped items wearing out while their action runs, and repairing them.
+
+use bevy::prelude::*;
+use itertools::Itertools as _;
+use smallvec::SmallVec;
+
+use crate::incremental::action::CurrentAction;
+use crate::incremental::item::{affixive_item::AffixiveItem, craft::Recipe, equipment::Unequipped, item_slot::ItemSlot};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stats::{RecalculateStats, StatId};
+use crate::incremental::stock::{StockKind, producer_consumer::StockTickMultiplier, stockyard::Stockyard};
+
+/// Fraction of the item's recipe cost it takes to repair it from broken.
+const REPAIR_RECIPE_FRACTION: f64 = 0.5;
+
+/// Event to fire when the player spends stocks to restore an item's durability.
+#[derive(Debug, Event)]
+pub struct RepairRequest {
+    /// Entity with the [AffixiveItem] to repair.
+    pub item: Entity,
+}
+
+/// Stocks it takes to repair the item, in proportion to how worn it is.
+///
+/// Empty for items that are not worn.
+pub fn repair_cost(item: &AffixiveItem, recipe: &Recipe) -> SmallVec<[(StockKind, f64); 2]> {
+    let Some(durability) = item.durability() else { return SmallVec::new(); };
+    let worn = 1.0 - durability.fraction();
+
+    recipe.resources.iter()
+    .map(|&(stock_kind, amount)| (stock_kind, (amount * REPAIR_RECIPE_FRACTION * worn * 10.0).ceil() / 10.0))
+    .filter(|&(_, amount)| amount > 0.0)
+    .collect()
+}
+
+/// Wears the equipped items that affect the stats of the current action.
+///
+/// Runs in the [StockTick](crate::incremental::stock::producer_consumer::StockTick) schedule,
+/// so items also wear while fast-forwarding.
+/// Broken items are taken out of their slot.
+pub(crate) fn wear_equipped_items(
+    mut commands: Commands,
+
+    time: Res<Time<Fixed>>,
+    tick_multiplier: Res<StockTickMultiplier>,
+    current_action: Res<CurrentAction>,
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_slot_query: Query<&mut ItemSlot>,
+    mut item_query: Query<&mut AffixiveItem>,
+) {
+    let Some(action_stats) = current_action.and_then(StatId::for_action) else { return; };
+    let wear = time.timestep().as_secs_f64() * **tick_multiplier;
+    let mut worn_visibly = false;
+
+    for mut item_slot in item_slot_query.iter_mut() {
+        let Some(item_entity) = item_slot.item else { continue; };
+        let Ok(mut item) = item_query.get_mut(item_entity) else { continue; };
+
+        if item.durability().is_none() {
+            continue;
+        }
+
+        let used = item.modifiers()
+        .flat_map(|(_, modifier, _)| modifier.kind.stat_effects().iter())
+        .any(|(stat, _)| action_stats.contains(stat));
+
+        if !used {
+            continue;
+        }
+
+        // Wearing happens every tick, so only changes that show are announced.
+        let percent_before = item.durability().map(|durability| durability.percent());
+        let broke = item.bypass_change_detection().wear(wear);
+
+        if item.durability().map(|durability| durability.percent()) != percent_before {
+            item.set_changed();
+            worn_visibly = true;
+        }
+
+        if broke {
+            messages.write(format!("'{}' broke.", item.name()).into());
+            item_slot.item = None;
+            commands.trigger(Unequipped { item: item_entity });
+        }
+    }
+
+    // Stats otherwise only pick up changed items in `Update`, which does not run while fast-forwarding.
+    if worn_visibly {
+        commands.trigger(RecalculateStats);
+    }
+}
+
+pub(super) fn on_repair_request(
+    event: On<RepairRequest>,
+
+    mut stockyard: ResMut<Stockyard>,
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_query: Query<&mut AffixiveItem>,
+    recipe_query: Query<&Recipe>,
+) {
+    let mut item = item_query.get_mut(event.item).expect("RepairRequest event's item entity must have an AffixiveItem component.");
+
+    let Some(durability) = item.durability() else {
+        messages.write(format!("'{}' does not wear out.", item.name()).into());
+        return;
+    };
+
+    if durability.current >= durability.maximum {
+        messages.write(format!("'{}' is not worn.", item.name()).into());
+        return;
+    }
+
+    // Without a recipe there is nothing to price the repair with.
+    let Some(recipe) = recipe_query.iter().find(|recipe| recipe.base == item.base()) else {
+        messages.write(format!("Unable to repair '{}'. There is no recipe for it.", item.name()).into());
+        return;
+    };
+
+    let cost = repair_cost(&item, recipe);
+
+    let has_sufficient_stock = cost.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount);
+
+    if !has_sufficient_stock {
+        messages.write(format!("Unable to repair '{}'. Insufficient resources.", item.name()).into());
+        return;
+    }
+
+    for &(stock_kind, amount) in cost.iter() {
+        stockyard[stock_kind] -= amount;
+    }
+
+    item.repair();
+
+    let cost_text = cost.iter().map(|&(stock_kind, amount)| format!("{} - {}", stock_kind, amount)).join("  ");
+    messages.write(format!("Repaired '{}' for {}.", item.name(), cost_text).into());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::prelude::*;
+
+    use crate::incremental::IncrementalPlugin;
+    use crate::incremental::action::{Action, ChangeAction};
+    use crate::incremental::item::{affixive_item::{AffixiveItem, ItemLevel}, base::Base, craft::Recipe, equipment::EquipRequest, item_database::ItemDatabase, item_slot::ItemSlot};
+    use crate::incremental::stock::{StockKind, producer_consumer::fast_forward_stocks, stockyard::Stockyard};
+
+    use super::repair_cost;
+
+    /// Makeshift tools, which last for 600 seconds.
+    fn makeshift_tools() -> AffixiveItem {
+        ItemDatabase::new().create_test_item(Base::MakeshiftTools)
+    }
+
+    fn makeshift_tools_recipe() -> Recipe {
+        Recipe {
+            base: Base::MakeshiftTools,
+            resources: smallvec::smallvec![(StockKind::Wood, 10.0), (StockKind::Stone, 4.0)],
+            item_level: ItemLevel(1),
+        }
+    }
+
+    #[test]
+    fn items_break_exactly_once_and_durability_stops_at_zero() {
+        let mut item = makeshift_tools();
+
+        assert!(!item.wear(599.0));
+        assert!(!item.is_broken());
+
+        assert!(item.wear(10.0));
+        assert!(item.is_broken());
+        assert_eq!(item.durability().map(|durability| durability.current), Some(0.0));
+
+        assert!(!item.wear(10.0));
+        assert_eq!(item.durability().map(|durability| durability.current), Some(0.0));
+    }
+
+    #[test]
+    fn wear_penalty_grows_to_half_as_durability_runs_out() {
+        let mut item = makeshift_tools();
+        assert_eq!(item.wear_penalty(), 0.0);
+
+        item.wear(300.0);
+        assert_eq!(item.wear_penalty(), -0.25);
+
+        item.wear(300.0);
+        assert_eq!(item.wear_penalty(), -0.5);
+
+        item.repair();
+        assert_eq!(item.wear_penalty(), 0.0);
+        assert!(!item.is_broken());
+    }
+
+    #[test]
+    fn repair_cost_scales_with_wear() {
+        let recipe = makeshift_tools_recipe();
+        let mut item = makeshift_tools();
+
+        assert!(repair_cost(&item, &recipe).is_empty());
+
+        item.wear(300.0);
+        assert_eq!(repair_cost(&item, &recipe).as_slice(), &[(StockKind::Wood, 2.5), (StockKind::Stone, 1.0)]);
+
+        item.wear(300.0);
+        assert_eq!(repair_cost(&item, &recipe).as_slice(), &[(StockKind::Wood, 5.0), (StockKind::Stone, 2.0)]);
+    }
+
+    #[test]
+    fn items_without_durability_never_wear() {
+        let mut item = ItemDatabase::new().create_test_item(Base::TestTools);
+
+        assert!(!item.wear(1_000_000.0));
+        assert!(!item.is_broken());
+        assert_eq!(item.wear_penalty(), 0.0);
+        assert!(repair_cost(&item, &makeshift_tools_recipe()).is_empty());
+    }
+
+    /// Wood gathered while fast-forwarding, starting from no wood so the maximum is not in the way.
+    fn fast_forward_wood(app: &mut App, duration: Duration) -> f64 {
+        let world = app.world_mut();
+
+        {
+            let mut stockyard = world.resource_mut::<Stockyard>();
+            let wood = stockyard[StockKind::Wood].current();
+            stockyard[StockKind::Wood] -= wood;
+        }
+
+        fast_forward_stocks(world, duration);
+        world.resource::<Stockyard>()[StockKind::Wood].current()
+    }
+
+    #[test]
+    fn items_breaking_while_fast_forwarding_stop_adding_to_gathering() {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, IncrementalPlugin));
+        app.finish();
+        app.cleanup();
+        app.update();
+
+        let world = app.world_mut();
+        let item = world.spawn(makeshift_tools()).id();
+        world.trigger(EquipRequest { item, preferred_slot: None });
+        world.trigger(ChangeAction::new(Action::GatherWood));
+
+        // Wood is only gathered with tools.
+        assert!(fast_forward_wood(&mut app, Duration::from_secs(10)) > 0.0);
+
+        fast_forward_wood(&mut app, Duration::from_secs(600));
+        let world = app.world_mut();
+        assert!(world.query::<&ItemSlot>().iter(world).all(|item_slot| item_slot.item != Some(item)));
+
+        assert_eq!(fast_forward_wood(&mut app, Duration::from_secs(10)), 0.0);
+    }
+}
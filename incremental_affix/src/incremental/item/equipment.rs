@@ -1,106 +1,110 @@
-use std::time::Duration;
-
 use bevy::prelude::*;
 
-use crate::incremental::affinity::Affinity;
-use crate::incremental::item::item_slot::ItemSlot;
-use crate::incremental::item::{affixive_item::AffixiveItem, modifier::ModifierKind};
-use crate::incremental::stats::PlayerActionsStats;
-use crate::incremental::DotPerSecond;
-use crate::stats_builder::StatsBuilder;
+use crate::incremental::item::affixive_item::AffixiveItem;
+use crate::incremental::item::item_slot::{ItemSlot, ItemSlotId};
 
+/// Event for when an item was put into an [ItemSlot].
+///
+/// Recalculates the player's stats right away.
 #[derive(Debug, EntityEvent)]
 pub struct Equipped {
     #[event_target]
     pub(crate) item: Entity
 }
 
-pub fn on_equip(
-    _equipped: On<Equipped>,
+/// Event for when an item was taken out of its [ItemSlot].
+///
+/// Recalculates the player's stats right away.
+#[derive(Debug, EntityEvent)]
+pub struct Unequipped {
+    #[event_target]
+    pub(crate) item: Entity
+}
+
+/// Event to fire when the player puts an item from the inventory into an item slot.
+///
+/// Whatever was in the slot goes back to the inventory.
+#[derive(Debug, Event)]
+pub struct EquipRequest {
+    pub item: Entity,
 
-    mut player_actions_stats: ResMut<PlayerActionsStats>,
+    /// Entity with the [ItemSlot] to put the item into if it accepts the item.
+    /// Otherwise the item goes into the first slot that accepts it.
+    pub preferred_slot: Option<Entity>,
+}
 
-    item_query: Query<&AffixiveItem>,
-    item_slot_query: Query<&ItemSlot>,
+/// Event to fire when the player takes the item out of the slot.
+#[derive(Debug, Event)]
+pub struct UnequipRequest {
+    pub slot: ItemSlotId,
+}
+
+pub(super) fn on_unequip_request(
+    event: On<UnequipRequest>,
+    mut commands: Commands,
+
+    mut item_slot_query: Query<&mut ItemSlot>,
+) {
+    let Some(mut item_slot) = item_slot_query.iter_mut().find(|item_slot| item_slot.id == event.slot) else {
+        warn!("Item slot {} is not in the item definitions.", event.slot);
+        return;
+    };
+
+    let Some(item) = item_slot.item.take() else { return; };
+
+    commands.trigger(Unequipped { item });
+}
+
+/// Put the item into the item slot with the id, taking it out of any other slot it is in.
+///
+/// Whatever was in the slot goes back to the inventory.
+/// Does nothing if the item is already there or the slot does not accept it.
+/// Triggers [Unequipped] and [Equipped] for every item that moved.
+pub(crate) fn equip_item(
+    mut commands: Commands,
+
+    item_entity: Entity,
+    item: &AffixiveItem,
+    slot_id: &ItemSlotId,
+    item_slot_query: &mut Query<&mut ItemSlot>,
 ) {
-    let mut base_wood = StatsBuilder::default();
-    let mut wood_affinity_chance = StatsBuilder::default();
-    let mut wood_affinity_multiplier = StatsBuilder::default();
-    let mut wood_affinity_time = StatsBuilder::default();
-
-    let mut base_stone = StatsBuilder::default();
-    let mut stone_affinity_chance = StatsBuilder::default();
-    let mut stone_affinity_multiplier = StatsBuilder::default();
-    let mut stone_affinity_time = StatsBuilder::default();
-
-    let mut base_hunt = StatsBuilder::default();
-
-    // Base values.
-    wood_affinity_chance.add_offset(0.5);
-    wood_affinity_multiplier.add_offset(2.0);
-    wood_affinity_time.add_offset(1.0);
-
-    stone_affinity_chance.add_offset(0.5);
-    stone_affinity_multiplier.add_offset(2.0);
-    stone_affinity_time.add_offset(1.0);
-
-    for item_slot in item_slot_query.iter() {
-        let Some(item) = item_slot.item else { continue; };
-        let item = item_query.get(item).unwrap();
-
-        for (modifier, value) in item.modifiers() {
-            match modifier.kind {
-                ModifierKind::WoodBase => { base_wood.set_base(value as f64 / 100.0); },
-                ModifierKind::WoodBaseGain => { base_wood.add_offset(value as f64 / 100.0); },
-                ModifierKind::WoodMultiplier => { base_wood.add_multiplier_percent(value); }
-                ModifierKind::WoodAffinityChanceMultiplier => { wood_affinity_chance.add_multiplier_percent(value); },
-                ModifierKind::WoodAffinityMultiplier => { wood_affinity_multiplier.add_multiplier_percent(value); },
-                ModifierKind::WoodAffinityTimeMultiplier => { wood_affinity_time.add_multiplier_percent(value); },
-
-                ModifierKind::StoneBase => { base_stone.set_base(value as f64 / 100.0); },
-                ModifierKind::StoneBaseGain => {base_stone.add_offset(value as f64 / 100.0); },
-                ModifierKind::StoneMultiplier => { base_stone.add_multiplier_percent(value); }
-                ModifierKind::StoneAffinityChanceMultiplier => { stone_affinity_chance.add_multiplier_percent(value); },
-                ModifierKind::StoneAffinityMultiplier => { stone_affinity_multiplier.add_multiplier_percent(value); },
-                ModifierKind::StoneAffinityTimeMultiplier => { stone_affinity_time.add_multiplier_percent(value); },
-
-                ModifierKind::ToolMultiplier => {
-                    base_wood.add_multiplier_percent(value);
-                    base_stone.add_multiplier_percent(value);
-                },
-                ModifierKind::ToolAffinityChanceMultiplier => {
-                    wood_affinity_chance.add_multiplier_percent(value);
-                    stone_affinity_chance.add_multiplier_percent(value);
-                },
-                ModifierKind::ToolAffinityMultiplier => {
-                    wood_affinity_multiplier.add_multiplier_percent(value);
-                    stone_affinity_multiplier.add_multiplier_percent(value);
-                },
-                ModifierKind::ToolAffinityTimeMultiplier => {
-                    wood_affinity_time.add_multiplier_percent(value);
-                    stone_affinity_time.add_multiplier_percent(value);
-                },
-
-                ModifierKind::HuntBase => {
-                    base_hunt.set_base(value as f64 / 100.0);
-                }
-            }
-        }
+    let Some(item_slot) = item_slot_query.iter().find(|item_slot| item_slot.id == *slot_id) else { return; };
+
+    if item_slot.item == Some(item_entity) || !item_slot.accepts(item) {
+        return;
     }
 
-    player_actions_stats.gather_wood.base_gain_per_second = base_wood.calculate().per_second();
-    player_actions_stats.gather_wood.affinity = Affinity {
-        chance: wood_affinity_chance.calculate(),
-        multiplier: wood_affinity_multiplier.calculate(),
-        time: Duration::from_secs_f64(wood_affinity_time.calculate()),
-    };
+    // The item might be in another slot.
+    for mut item_slot in item_slot_query.iter_mut().filter(|item_slot| item_slot.item == Some(item_entity)) {
+        item_slot.item = None;
+    }
 
-    player_actions_stats.gather_stone.base_gain_per_second = base_stone.calculate().per_second();
-    player_actions_stats.gather_stone.affinity = Affinity {
-        chance: stone_affinity_chance.calculate(),
-        multiplier: stone_affinity_multiplier.calculate(),
-        time: Duration::from_secs_f64(stone_affinity_time.calculate()),
-    };
+    let mut item_slot = item_slot_query.iter_mut().find(|item_slot| item_slot.id == *slot_id)
+    .expect("The item slot was checked to exist.");
+
+    if let Some(previous_item) = item_slot.item.replace(item_entity) {
+        commands.trigger(Unequipped { item: previous_item });
+    }
+
+    commands.trigger(Equipped { item: item_entity });
+}
+
+pub(super) fn on_equip_request(
+    event: On<EquipRequest>,
+    commands: Commands,
+
+    item_query: Query<&AffixiveItem>,
+    mut item_slot_query: Query<&mut ItemSlot>,
+) {
+    let item = item_query.get(event.item).expect("EquipRequest event's item entity must have an AffixiveItem component.");
+
+    let preferred_slot = event.preferred_slot
+    .and_then(|slot_entity| item_slot_query.get(slot_entity).ok())
+    .filter(|item_slot| item_slot.accepts(item));
 
-    player_actions_stats.hunt.base_gain_per_second = base_hunt.calculate().per_second();
-}
\ No newline at end of file
+    let Some(slot_id) = preferred_slot
+    .or_else(|| item_slot_query.iter().find(|item_slot| item_slot.accepts(item)))
+    .map(|item_slot| item_slot.id.clone()) else { return; };
+
+    equip_item(commands, event.item, item, &slot_id, &mut item_slot_query);
+}
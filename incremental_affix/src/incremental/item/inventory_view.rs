@@ -0,0 +1,197 @@
+//! Which inventory items to show and in what order.
+//!
+//! The view only decides how the items are presented. The item entities themselves are never reordered.
+
+use std::cmp::Ordering;
+
+use bevy::prelude::*;
+
+use crate::incremental::item::{affixive_item::{AffixiveItem, ItemTag}, modifier::ModifierKind};
+use crate::incremental::stats::StatId;
+
+/// How to order the items in the inventory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InventorySort {
+    /// Keeps the order the items are already shown in.
+    #[default]
+    Unsorted,
+    Name,
+    Base,
+    /// Highest rarity first.
+    Rarity,
+    /// Highest value of the [InventoryView::sort_stat] if the item were equipped first.
+    Stat,
+}
+
+impl InventorySort {
+    pub const LIST: &[Self] = &[Self::Unsorted, Self::Name, Self::Base, Self::Rarity, Self::Stat];
+}
+
+impl std::fmt::Display for InventorySort {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unsorted => "Unsorted",
+            Self::Name => "Name",
+            Self::Base => "Base",
+            Self::Rarity => "Rarity",
+            Self::Stat => "Stat",
+        })
+    }
+}
+
+/// The sorting, filters and search the player chose for the inventory.
+#[derive(Debug, Clone, Default, Resource)]
+pub struct InventoryView {
+    pub sort: InventorySort,
+
+    /// Stat to sort by when sorting by [InventorySort::Stat].
+    pub sort_stat: StatId,
+
+    /// Only show items with the tag.
+    pub tag: Option<ItemTag>,
+
+    /// Only show items with a modifier of the kind.
+    pub modifier_kind: Option<ModifierKind>,
+
+    /// Only show items with an affix, or a socketed rune's name or text, that contains the search, ignoring case.
+    pub search: String,
+}
+
+impl InventoryView {
+    /// Whether the item passes the filters and search.
+    pub fn shows(&self, item: &AffixiveItem) -> bool {
+        if let Some(tag) = self.tag && !item.tags.contains(&tag) {
+            return false;
+        }
+
+        if let Some(kind) = self.modifier_kind && !item.modifiers().any(|(_, modifier, _)| modifier.kind == kind) {
+            return false;
+        }
+
+        if self.search.is_empty() {
+            return true;
+        }
+
+        let search = self.search.to_lowercase();
+
+        let rune_texts = item.runes().iter()
+        .flat_map(|rune| [rune.name().to_string(), rune.display()]);
+
+        item.implicits().chain(item.prefixes()).chain(item.suffixes())
+        .map(|affix| affix.display())
+        .chain(rune_texts)
+        .any(|text| text.to_lowercase().contains(&search))
+    }
+
+    /// The items to show, in the order to show them.
+    ///
+    /// Items that compare equal, and every item when unsorted, keep the order they were given in.
+    ///
+    /// `stat_value` is the value of the [sort stat](Self::sort_stat) if the item were equipped,
+    /// and is only used when sorting by stat.
+    pub fn arrange<'a>(
+        &self,
+        items: impl IntoIterator<Item = (Entity, &'a AffixiveItem)>,
+        stat_value: impl Fn(Entity) -> Option<f64>,
+    ) -> Vec<Entity> {
+        let mut shown = items.into_iter()
+        .filter(|&(_, item)| self.shows(item))
+        .collect::<Vec<_>>();
+
+        match self.sort {
+            InventorySort::Unsorted => {},
+            InventorySort::Name => shown.sort_by_cached_key(|&(_, item)| item.name().to_lowercase()),
+            InventorySort::Base => shown.sort_by_cached_key(|&(_, item)| item.base().to_string()),
+            InventorySort::Rarity => shown.sort_by_key(|&(_, item)| std::cmp::Reverse(item.rarity())),
+            InventorySort::Stat => {
+                let mut with_values = shown.into_iter()
+                .map(|(entity, item)| (entity, item, stat_value(entity)))
+                .collect::<Vec<_>>();
+
+                // Items that cannot be equipped go last.
+                with_values.sort_by(|(_, _, a), (_, _, b)| match (a, b) {
+                    (Some(a), Some(b)) => b.partial_cmp(a).unwrap_or(Ordering::Equal),
+                    (Some(_), None) => Ordering::Less,
+                    (None, Some(_)) => Ordering::Greater,
+                    (None, None) => Ordering::Equal,
+                });
+
+                return with_values.into_iter().map(|(entity, _, _)| entity).collect();
+            },
+        }
+
+        shown.into_iter().map(|(entity, _)| entity).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::prelude::*;
+
+    use crate::incremental::item::affixive_item::ItemTag;
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::item_database::ItemDatabase;
+
+    use super::{InventorySort, InventoryView};
+
+    #[test]
+    fn filters_by_tag_and_searches_affixes_ignoring_case() {
+        let item_db = ItemDatabase::new();
+        let mut tools = item_db.create_test_item(Base::StoneTools);
+        let hide_wrap = item_db.create_test_item(Base::HideWrap);
+
+        let tool_view = InventoryView { tag: Some(ItemTag::Tool), ..default() };
+        assert!(tool_view.shows(&tools));
+        assert!(!tool_view.shows(&hide_wrap));
+
+        item_db.try_push_random_prefix(&mut tools).expect("Stone tools must be able to roll a prefix.");
+        let prefix = tools.prefixes().next().expect("Stone tools must have the rolled prefix.").display();
+
+        let search_view = InventoryView { search: prefix.to_uppercase(), ..default() };
+        assert!(search_view.shows(&tools));
+        assert!(!search_view.shows(&hide_wrap));
+    }
+
+    #[test]
+    fn arranges_shown_items_by_stat_with_unequippable_items_last() {
+        let item_db = ItemDatabase::new();
+        let items = [
+            item_db.create_test_item(Base::StoneTools),
+            item_db.create_test_item(Base::StoneTools),
+            item_db.create_test_item(Base::StoneTools),
+            item_db.create_test_item(Base::HideWrap),
+        ];
+
+        let mut world = World::new();
+        let entities = items.each_ref().map(|_| world.spawn_empty().id());
+        let stat_value = |entity| match entities.iter().position(|&e| e == entity) {
+            Some(0) => None,
+            Some(1) => Some(1.0),
+            Some(2) => Some(3.0),
+            _ => Some(2.0),
+        };
+
+        let unsorted = InventoryView { tag: Some(ItemTag::Tool), ..default() };
+        assert_eq!(unsorted.arrange(entities.into_iter().zip(&items), stat_value), entities[..3]);
+
+        let by_stat = InventoryView { sort: InventorySort::Stat, tag: Some(ItemTag::Tool), ..default() };
+        assert_eq!(by_stat.arrange(entities.into_iter().zip(&items), stat_value), [entities[2], entities[1], entities[0]]);
+    }
+
+    #[test]
+    fn arranges_rarer_items_first_keeping_the_order_of_equals() {
+        let item_db = ItemDatabase::new();
+        let mut items = [
+            item_db.create_test_item(Base::StoneTools),
+            item_db.create_test_item(Base::StoneTools),
+            item_db.create_test_item(Base::StoneTools),
+        ];
+        item_db.try_push_random_prefix(&mut items[1]).expect("Stone tools must be able to roll a prefix.");
+
+        let mut world = World::new();
+        let entities = items.each_ref().map(|_| world.spawn_empty().id());
+
+        let by_rarity = InventoryView { sort: InventorySort::Rarity, ..default() };
+        assert_eq!(by_rarity.arrange(entities.into_iter().zip(&items), |_| None), [entities[1], entities[0], entities[2]]);
+    }
+}
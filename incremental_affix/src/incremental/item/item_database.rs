@@ -1,49 +1,120 @@
-use std::ops::Range;
-
 use bevy::{platform::collections::HashMap, prelude::Resource};
-use rand::Rng;
+use rand::seq::IndexedRandom as _;
 
-use crate::incremental::item::affixive_item::{AffixiveItem, ItemTag, Quality};
+use crate::incremental::item::affixive_item::{AffixiveItem, ItemLevel, Quality};
 use crate::incremental::item::base::{AffixiveItemBase, Base};
-use crate::incremental::item::modifier::{Implicit, Prefix, Suffix};
+use crate::incremental::item::definitions::{AffixFamilyDefinition, AffixKind, ArtifactDefinition, ArtifactId, ItemDefinitionError, ItemDefinitions, RuneDefinition, SetDefinition, SetId, SlotDefinition, display_errors};
+use crate::incremental::item::modifier::{Affix, AffixId, Implicit, Prefix, Suffix};
 
 use super::affixive_item::PushAffixError;
 
-pub type AffixTable = Range<usize>;
+/// Ids of the affix families that can roll on a base.
+pub type AffixTable = Vec<AffixId>;
 
 #[derive(Debug, Resource)]
 pub struct ItemDatabase {
     bases: HashMap<Base, AffixiveItemBase>,
-    implicits: Vec<Implicit>,
-    prefixes: Vec<Prefix>,
-    suffixes: Vec<Suffix>,
+    implicits: HashMap<AffixId, Implicit>,
+    prefixes: HashMap<AffixId, AffixFamilyDefinition>,
+    suffixes: HashMap<AffixId, AffixFamilyDefinition>,
 
     prefix_table: HashMap<Base, AffixTable>,
     suffix_table: HashMap<Base, AffixTable>,
+
+    artifacts: HashMap<ArtifactId, ArtifactDefinition>,
+    sets: HashMap<SetId, SetDefinition>,
+    runes: Vec<RuneDefinition>,
+
+    slots: Vec<SlotDefinition>,
 }
 
 impl ItemDatabase {
+    /// Build the database from the item definitions compiled into the game.
+    ///
+    /// Panics listing every problem if the built in definitions are invalid.
     pub fn new() -> Self {
-        let bases = super::base::initialize();
-        let implicits = super::modifier::initialize_implicits();
-        let prefixes = super::modifier::initialize_prefixes();
-        let suffixes = super::modifier::initialize_suffixes();
+        match Self::from_definitions(&ItemDefinitions::builtin()) {
+            Ok(item_db) => item_db,
+            Err(errors) => panic!("Built in item definitions must be valid:\n{}", display_errors(&errors)),
+        }
+    }
+
+    /// Build the database from validated definitions.
+    pub fn from_definitions(definitions: &ItemDefinitions) -> Result<Self, Vec<ItemDefinitionError>> {
+        definitions.validate()?;
+
+        fn families(definitions: &[AffixFamilyDefinition]) -> HashMap<AffixId, AffixFamilyDefinition> {
+            definitions.iter()
+            .map(|definition| (definition.id.clone(), definition.clone()))
+            .collect()
+        }
+
+        let bases = definitions.bases.iter()
+        .map(|definition| (definition.base, AffixiveItemBase {
+            name: definition.name.clone().into(),
+            tags: definition.tags.clone(),
+            implicits: definition.implicits.clone(),
+            durability: definition.durability,
+            set: definition.set.clone(),
+            sockets: definition.sockets,
+        }))
+        .collect();
+
+        let implicits = definitions.implicits.iter()
+        .map(|definition| (definition.id.clone(), Implicit(Affix::new(definition.name.clone(), definition.modifier))))
+        .collect();
+        let prefixes = families(&definitions.prefixes);
+        let suffixes = families(&definitions.suffixes);
+
+        let prefix_table = definitions.affix_pools.iter()
+        .map(|pool| (pool.base, pool.prefixes.clone()))
+        .collect();
+
+        let suffix_table = definitions.affix_pools.iter()
+        .map(|pool| (pool.base, pool.suffixes.clone()))
+        .collect();
+
+        let artifacts = definitions.artifacts.iter()
+        .map(|definition| (definition.id.clone(), definition.clone()))
+        .collect();
+
+        let sets = definitions.sets.iter()
+        .map(|definition| (definition.id.clone(), definition.clone()))
+        .collect();
+
+        let runes = definitions.runes.clone();
+        let slots = definitions.slots.clone();
 
-        let mut prefix_table: HashMap<Base, AffixTable> = HashMap::new();
-        prefix_table.insert(Base::StoneTools, 0..prefixes.len());
-        prefix_table.insert(Base::MakeshiftTools, 0..prefixes.len());
-        prefix_table.insert(Base::TestTools, 0..prefixes.len());
+        Ok(Self { bases, implicits, prefixes, suffixes, prefix_table, suffix_table, artifacts, sets, runes, slots })
+    }
 
-        let mut suffix_table: HashMap<Base, AffixTable> = HashMap::new();
-        suffix_table.insert(Base::StoneTools, 0..suffixes.len());
-        suffix_table.insert(Base::MakeshiftTools, 0..suffixes.len());
-        suffix_table.insert(Base::TestTools, 0..suffixes.len());
+    /// The runes in the order they are defined.
+    pub fn runes(&self) -> &[RuneDefinition] {
+        &self.runes
+    }
 
-        Self { bases, implicits, prefixes, suffixes, prefix_table, suffix_table }
+    pub fn set(&self, id: &SetId) -> Option<&SetDefinition> {
+        self.sets.get(id)
     }
 
-    pub fn item_has_tag(&self, item: &AffixiveItem, tag: ItemTag) -> bool {
-        item.tags.contains(&tag)
+    /// Names of the bases and artifacts that are pieces of the set, sorted by name.
+    pub fn set_pieces(&self, id: &SetId) -> Vec<&str> {
+        let bases = self.bases.values()
+        .filter(|base| base.set.as_ref() == Some(id))
+        .map(|base| base.name.as_ref());
+
+        let artifacts = self.artifacts.values()
+        .filter(|artifact| artifact.set.as_ref() == Some(id))
+        .map(|artifact| artifact.name.as_str());
+
+        let mut pieces: Vec<&str> = bases.chain(artifacts).collect();
+        pieces.sort_unstable();
+        pieces
+    }
+
+    /// The item slots in the order they are shown in the inventory.
+    pub fn slots(&self) -> &[SlotDefinition] {
+        &self.slots
     }
 
     #[allow(unused, reason = "Debug function")]
@@ -59,35 +130,151 @@ impl ItemDatabase {
         &self.suffix_table[&item.base()]
     }
 
+    /// The prefix or suffix family with the id.
+    pub fn affix_family(&self, kind: AffixKind, id: &AffixId) -> Option<&AffixFamilyDefinition> {
+        match kind {
+            AffixKind::Implicit => None,
+            AffixKind::Prefix => self.prefixes.get(id),
+            AffixKind::Suffix => self.suffixes.get(id),
+        }
+    }
+
+    /// Make a rolled affix of the family's tier (starting at 1) for the item.
+    ///
+    /// Returns `None` if the family cannot roll on the item's base or the tier is above the item's level.
+    pub fn create_affix_for(&self, item: &AffixiveItem, kind: AffixKind, id: &AffixId, tier: u8) -> Option<Affix> {
+        let table = match kind {
+            AffixKind::Implicit => return None,
+            AffixKind::Prefix => self.prefix_table(item),
+            AffixKind::Suffix => self.suffix_table(item),
+        };
+
+        if !table.contains(id) {
+            return None;
+        }
+
+        let family = self.affix_family(kind, id)?;
+        let index = (tier as usize).checked_sub(1)?;
+
+        if family.tiers.get(index)?.level > item.item_level() {
+            return None;
+        }
+
+        let mut affix = family.tier_affix(index);
+        affix.randomize_actual();
+        Some(affix)
+    }
+
     /// Make a new item with no modifiers or modifier slots of the specified base.
-    pub fn create_basic(&self, base: Base) -> AffixiveItem {
-        let implicits = &*self.implicits;
-        AffixiveItem::new(&self.bases, implicits, base, Quality::Quality(0))
+    pub fn create_basic(&self, base: Base, item_level: ItemLevel) -> AffixiveItem {
+        AffixiveItem::new(&self.bases, &self.implicits, base, Quality::Quality(0), item_level)
+    }
+
+    /// Make the artifact with its values rolled. Returns `None` if there is no such artifact.
+    pub fn create_artifact(&self, id: &ArtifactId) -> Option<AffixiveItem> {
+        let definition = self.artifacts.get(id)?;
+        Some(AffixiveItem::new_artifact(&self.bases, definition))
     }
 
     /// Try to push a random prefix that can be put onto the item onto it.
-    pub fn try_push_random_prefix(&self, item: &mut AffixiveItem) -> Result<(), PushAffixError> {
-        let mut rng = rand::rng();
-        let table = self.prefix_table(item);
-        let index = rng.random_range(table.clone());
-        let mut prefix = self.prefixes[index].clone();
-        prefix.randomize_actual();
-        item.try_push_prefix(prefix)
+    ///
+    /// Returns a copy of the pushed prefix.
+    pub fn try_push_random_prefix(&self, item: &mut AffixiveItem) -> Result<Affix, PushAffixError> {
+        item.can_push_prefix()?;
+        let prefix = roll_affix(&self.prefixes, self.prefix_table(item), item).ok_or(PushAffixError::NoEligibleAffix)?;
+        item.try_push_prefix(Prefix(prefix.clone()))?;
+        Ok(prefix)
     }
 
     /// Try to push a random suffix that can be put onto the item onto it.
-    pub fn try_push_random_suffix(&self, item: &mut AffixiveItem) -> Result<(), PushAffixError> {
-        let mut rng = rand::rng();
-        let table = self.suffix_table(item);
-        let index = rng.random_range(table.clone());
-        let mut suffix = self.suffixes[index].clone();
-        suffix.randomize_actual();
-        item.try_push_suffix(suffix)
+    ///
+    /// Returns a copy of the pushed suffix.
+    pub fn try_push_random_suffix(&self, item: &mut AffixiveItem) -> Result<Affix, PushAffixError> {
+        item.can_push_suffix()?;
+        let suffix = roll_affix(&self.suffixes, self.suffix_table(item), item).ok_or(PushAffixError::NoEligibleAffix)?;
+        item.try_push_suffix(Suffix(suffix.clone()))?;
+        Ok(suffix)
     }
 }
 
+/// Pick a tier from the families in the table whose group is not already on the item
+/// and that can roll at the item's level, weighted by the tiers' weights, and roll its value.
+///
+/// Returns `None` when there is no such tier.
+fn roll_affix(families: &HashMap<AffixId, AffixFamilyDefinition>, table: &AffixTable, item: &AffixiveItem) -> Option<Affix> {
+    let item_level = item.item_level();
+
+    let candidates: Vec<(&AffixFamilyDefinition, usize, u32)> = table.iter()
+    .map(|id| &families[id])
+    .filter(|family| !item.has_modifier_group(&family.group))
+    .flat_map(|family| family.tiers.iter().enumerate()
+        .filter(|(_, tier)| tier.level <= item_level)
+        .map(move |(index, tier)| (family, index, tier.weight))
+    )
+    .collect();
+
+    let &(family, index, _) = candidates.choose_weighted(&mut rand::rng(), |&(_, _, weight)| weight).ok()?;
+
+    let mut affix = family.tier_affix(index);
+    affix.randomize_actual();
+    Some(affix)
+}
+
 impl Default for ItemDatabase {
     fn default() -> Self {
         Self::new()
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+impl ItemDatabase {
+    /// A basic item of the base at the lowest item level, with room for a prefix and a suffix.
+    pub(crate) fn create_test_item(&self, base: Base) -> AffixiveItem {
+        let mut item = self.create_basic(base, ItemLevel::MIN);
+        item.increase_quality_to(1);
+        item
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::affixive_item::ItemLevel;
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::definitions::{AffixKind, ItemDefinitions};
+    use crate::incremental::item::modifier::AffixId;
+
+    use super::ItemDatabase;
+
+    #[test]
+    fn tiers_above_the_item_level_cannot_be_added() {
+        let item_db = ItemDatabase::new();
+        let id = AffixId("wood_base_gain".into());
+        let tier_2_level = item_db.affix_family(AffixKind::Prefix, &id).expect("Shipped item definitions must have the prefix.").tiers[1].level;
+
+        let low_item = item_db.create_basic(Base::StoneTools, ItemLevel(tier_2_level.0 - 1));
+        assert!(item_db.create_affix_for(&low_item, AffixKind::Prefix, &id, 1).is_some());
+        assert!(item_db.create_affix_for(&low_item, AffixKind::Prefix, &id, 2).is_none());
+
+        let item = item_db.create_basic(Base::StoneTools, tier_2_level);
+        assert_eq!(item_db.create_affix_for(&item, AffixKind::Prefix, &id, 2).and_then(|affix| affix.tier), Some(2));
+    }
+
+    #[test]
+    fn tiers_above_the_item_level_never_roll() {
+        let item_db = ItemDatabase::new();
+        let definitions = ItemDefinitions::builtin();
+
+        for _ in 0..200 {
+            let mut item = item_db.create_test_item(Base::StoneTools);
+            let prefix = item_db.try_push_random_prefix(&mut item).expect("Stone tools must be able to roll a prefix.");
+            let suffix = item_db.try_push_random_suffix(&mut item).expect("Stone tools must be able to roll a suffix.");
+
+            for (affix, families) in [(prefix, &definitions.prefixes), (suffix, &definitions.suffixes)] {
+                let family = families.iter().find(|family| family.name == affix.name).expect("Rolled affix must be from a defined family.");
+                let tier = affix.tier.expect("Rolled affixes must have a tier.");
+
+                assert!(family.tiers[tier as usize - 1].level <= ItemLevel::MIN, "{} rolled tier {} above the item level.", affix.name, tier);
+            }
+        }
+    }
+}
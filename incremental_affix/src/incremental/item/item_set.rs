@@ -0,0 +1,112 @@
+//! Items that grant bonuses when different pieces of the same set are equipped together.
+//!
+//! Sets and their bonuses are defined in the item definitions.
+//! See [SetDefinition](crate::incremental::item::definitions::SetDefinition).
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::incremental::item::{affixive_item::AffixiveItem, definitions::SetId, item_database::ItemDatabase, item_slot::ItemSlot};
+
+/// The different pieces of each set among the items.
+///
+/// Two items of the same base count as one piece.
+pub fn set_pieces<'a>(items: impl IntoIterator<Item = &'a AffixiveItem>) -> HashMap<&'a SetId, Vec<&'a str>> {
+    let mut pieces: HashMap<&SetId, Vec<&str>> = HashMap::new();
+
+    for item in items {
+        let Some(set) = item.set() else { continue; };
+        let set_pieces = pieces.entry(set).or_default();
+
+        if !set_pieces.contains(&item.set_piece()) {
+            set_pieces.push(item.set_piece());
+        }
+    }
+
+    pieces
+}
+
+/// What to show about an item's set.
+#[derive(Debug, Clone)]
+pub struct SetDetails {
+    pub name: String,
+
+    /// Name of each piece of the set and whether it is equipped.
+    pub pieces: Vec<(String, bool)>,
+
+    /// Each bonus of the set and whether it is active.
+    pub bonuses: Vec<(String, bool)>,
+}
+
+/// The sets of the equipped items, for showing an item's set.
+#[derive(SystemParam)]
+pub struct EquippedSets<'w, 's> {
+    item_db: Res<'w, ItemDatabase>,
+    item_query: Query<'w, 's, &'static AffixiveItem>,
+    item_slot_query: Query<'w, 's, &'static ItemSlot>,
+}
+
+impl EquippedSets<'_, '_> {
+    /// Details of the item's set with what is currently equipped.
+    ///
+    /// Returns `None` if the item is not a piece of a set.
+    pub fn details(&self, item: &AffixiveItem) -> Option<SetDetails> {
+        let set_id = item.set()?;
+        let set = self.item_db.set(set_id)?;
+
+        let equipped_items = self.item_slot_query.iter()
+        .filter_map(|item_slot| item_slot.item)
+        .filter_map(|item_entity| self.item_query.get(item_entity).ok());
+
+        let equipped_pieces = set_pieces(equipped_items).remove(set_id).unwrap_or_default();
+
+        let pieces = self.item_db.set_pieces(set_id).into_iter()
+        .map(|piece| (piece.to_string(), equipped_pieces.contains(&piece)))
+        .collect();
+
+        let bonuses = set.bonuses.iter()
+        .map(|bonus| (bonus.display(), bonus.pieces <= equipped_pieces.len()))
+        .collect();
+
+        Some(SetDetails { name: set.name.clone(), pieces, bonuses })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::definitions::SetId;
+    use crate::incremental::item::item_database::ItemDatabase;
+
+    use super::set_pieces;
+
+    #[test]
+    fn only_different_pieces_count_towards_set_bonuses() {
+        let item_db = ItemDatabase::new();
+        let set_id = SetId("survivors_kit".into());
+        let set = item_db.set(&set_id).expect("Shipped item definitions must have the survivor's kit.");
+
+        let tools = item_db.create_test_item(Base::StoneTools);
+        let more_tools = item_db.create_test_item(Base::StoneTools);
+        let hunt = item_db.create_test_item(Base::WoodenHunt);
+
+        let pieces = set_pieces([&tools, &more_tools]);
+        let equipped = pieces.get(&set_id).expect("Stone tools must be a piece of the survivor's kit.");
+        assert_eq!(equipped.len(), 1);
+        assert_eq!(set.active_bonuses(equipped.len()).count(), 0);
+
+        let pieces = set_pieces([&tools, &more_tools, &hunt]);
+        let equipped = pieces.get(&set_id).expect("Stone tools must be a piece of the survivor's kit.");
+        assert_eq!(*equipped, item_db.set_pieces(&set_id));
+        assert_eq!(set.active_bonuses(equipped.len()).count(), set.bonuses.len());
+    }
+
+    #[test]
+    fn items_outside_sets_are_not_pieces() {
+        let item_db = ItemDatabase::new();
+        let makeshift_tools = item_db.create_test_item(Base::MakeshiftTools);
+
+        assert!(set_pieces([&makeshift_tools]).is_empty());
+    }
+}
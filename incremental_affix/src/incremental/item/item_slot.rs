@@ -1,23 +1,168 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::incremental::item::affixive_item::{AffixiveItem, ItemTag};
+use crate::incremental::item::definitions::SlotDefinition;
+use crate::incremental::item::equipment::Unequipped;
+use crate::incremental::item::item_database::ItemDatabase;
+use crate::incremental::log::LogEntry;
 
 #[derive(Debug, Component, Clone, Default)]
 pub struct ItemSlot {
-    pub tag: ItemSlotTag,
+    pub id: ItemSlotId,
+    pub name: String,
     pub item: Option<Entity>,
+
+    /// Items with any of these tags can be put into the slot.
+    pub accepts: SmallVec<[ItemTag; 2]>,
+
+    /// Locked slots are hidden and accept nothing until the story unlocks them.
+    pub unlocked: bool,
 }
 
-#[derive(Debug, Component, Clone, Copy, PartialEq, Eq, Hash, Default)]
-pub enum ItemSlotTag {
-    #[default] // To allow for ItemSlot to be used in BSN.
-    Tool,
-    Hunt,
+impl ItemSlot {
+    /// Whether the item can be put into this slot. Broken items cannot be put into any slot.
+    pub fn accepts(&self, item: &AffixiveItem) -> bool {
+        self.unlocked && !item.is_broken() && item.tags.iter().any(|tag| self.accepts.contains(tag))
+    }
 }
 
-impl std::fmt::Display for ItemSlotTag {
+impl From<&SlotDefinition> for ItemSlot {
+    fn from(definition: &SlotDefinition) -> Self {
+        Self {
+            id: definition.id.clone(),
+            name: definition.name.clone(),
+            item: None,
+            accepts: definition.accepts.clone(),
+            unlocked: !definition.locked,
+        }
+    }
+}
+
+/// Stable identifier of an item slot in the item definitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ItemSlotId(pub String);
+
+impl std::fmt::Display for ItemSlotId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.write_str(match *self {
-            ItemSlotTag::Tool => "Tools",
-            ItemSlotTag::Hunt => "Hunting Gear",
-        })
+        f.write_str(&self.0)
+    }
+}
+
+/// Event to fire when the story lets the player use an item slot.
+#[derive(Debug, Event)]
+pub struct UnlockItemSlot {
+    pub slot: ItemSlotId,
+}
+
+/// Spawns an item slot entity for each slot in the item definitions.
+pub(super) fn initialize_item_slots(
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+) {
+    commands.spawn_batch(item_db.slots().iter().map(ItemSlot::from).collect::<Vec<_>>());
+}
+
+/// Keeps the item slots in line with reloaded item definitions.
+///
+/// Slots keep their item and whether they were unlocked.
+/// Slots that are no longer defined are removed, returning their item to the inventory.
+pub(super) fn update_item_slots(
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+
+    mut item_slot_query: Query<(Entity, &mut ItemSlot)>,
+) {
+    if !item_db.is_changed() || item_db.is_added() {
+        return;
+    }
+
+    for definition in item_db.slots() {
+        match item_slot_query.iter_mut().find(|(_, item_slot)| item_slot.id == definition.id) {
+            Some((_, mut item_slot)) => {
+                item_slot.name = definition.name.clone();
+                item_slot.accepts = definition.accepts.clone();
+            },
+            None => {
+                commands.spawn(ItemSlot::from(definition));
+            },
+        }
+    }
+
+    for (entity, mut item_slot) in item_slot_query.iter_mut() {
+        if item_db.slots().iter().any(|definition| definition.id == item_slot.id) {
+            continue;
+        }
+
+        if let Some(item) = item_slot.item.take() {
+            commands.trigger(Unequipped { item });
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
+
+pub(super) fn on_unlock_item_slot(
+    event: On<UnlockItemSlot>,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_slot_query: Query<&mut ItemSlot>,
+) {
+    let Some(mut item_slot) = item_slot_query.iter_mut().find(|item_slot| item_slot.id == event.slot) else {
+        warn!("Item slot {} is not in the item definitions.", event.slot);
+        return;
+    };
+
+    if item_slot.unlocked {
+        return;
+    }
+
+    item_slot.unlocked = true;
+    messages.write(format!("You can now equip {}.", item_slot.name).into());
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::affixive_item::ItemTag;
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::item_database::ItemDatabase;
+
+    use super::{ItemSlot, ItemSlotId};
+
+    fn altar_relic_slot(unlocked: bool) -> ItemSlot {
+        ItemSlot {
+            id: ItemSlotId("altar_relic".into()),
+            name: "Altar Relic".into(),
+            item: None,
+            accepts: smallvec::smallvec![ItemTag::Relic, ItemTag::Totem],
+            unlocked,
+        }
+    }
+
+    #[test]
+    fn slots_accept_items_with_any_of_their_tags() {
+        let item_db = ItemDatabase::new();
+        let item_slot = altar_relic_slot(true);
+
+        assert!(item_slot.accepts(&item_db.create_test_item(Base::StoneIdol)));
+        assert!(item_slot.accepts(&item_db.create_test_item(Base::BoneTotem)));
+        assert!(!item_slot.accepts(&item_db.create_test_item(Base::StoneTools)));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn locked_slots_accept_nothing_until_unlocked() {
+        let item_db = ItemDatabase::new();
+        let relic = item_db.create_test_item(Base::StoneIdol);
+        let mut item_slot = altar_relic_slot(false);
+
+        assert!(!item_slot.accepts(&relic));
+
+        item_slot.unlocked = true;
+        assert!(item_slot.accepts(&relic));
+    }
+}
@@ -0,0 +1,134 @@
+//! Named sets of equipment the player can switch to at once.
+//!
+//! A loadout bound to an [Action] is equipped whenever the player changes to that action.
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+
+use crate::incremental::action::Action;
+use crate::incremental::item::{affixive_item::AffixiveItem, equipment::equip_item, item_slot::{ItemSlot, ItemSlotId}};
+use crate::incremental::log::LogEntry;
+
+/// Items to put into item slots together.
+///
+/// Item slots the loadout has no item for keep whatever is in them.
+#[derive(Debug, Clone, Component)]
+pub struct Loadout {
+    pub name: String,
+    pub items: HashMap<ItemSlotId, Entity>,
+
+    /// Action that equips the loadout when the player changes to it.
+    pub action: Option<Action>,
+}
+
+/// Event to fire to save what is in the item slots as a new loadout.
+#[derive(Debug, Event)]
+pub struct SaveLoadoutRequest;
+
+/// Event to fire to put the items of a loadout into their slots.
+#[derive(Debug, Event)]
+pub struct EquipLoadoutRequest {
+    pub loadout: Entity,
+}
+
+/// Event to fire to make a loadout equip when changing to the action, or to stop it with `None`.
+///
+/// Any other loadout bound to the action is unbound.
+#[derive(Debug, Event)]
+pub struct BindLoadoutRequest {
+    pub loadout: Entity,
+    pub action: Option<Action>,
+}
+
+/// Event to fire to forget a loadout. The items stay where they are.
+#[derive(Debug, Event)]
+pub struct DeleteLoadoutRequest {
+    pub loadout: Entity,
+}
+
+/// Put the loadout's items into their slots with [equip_item].
+///
+/// Items that no longer exist or that the slot does not accept are skipped.
+pub fn equip_loadout(
+    mut commands: Commands,
+
+    loadout: &Loadout,
+    item_slot_query: &mut Query<&mut ItemSlot>,
+    item_query: &Query<&AffixiveItem>,
+) {
+    for (slot_id, &item_entity) in loadout.items.iter() {
+        let Ok(item) = item_query.get(item_entity) else { continue; };
+
+        equip_item(commands.reborrow(), item_entity, item, slot_id, item_slot_query);
+    }
+}
+
+/// Name for the next new loadout, e.g. "Loadout 3".
+fn next_loadout_name(loadout_query: &Query<&Loadout>) -> String {
+    let number = (1..)
+    .find(|number| {
+        let name = format!("Loadout {}", number);
+        loadout_query.iter().all(|loadout| loadout.name != name)
+    })
+    .expect("There is always an unused loadout number.");
+
+    format!("Loadout {}", number)
+}
+
+pub(super) fn on_save_loadout_request(
+    _event: On<SaveLoadoutRequest>,
+    mut commands: Commands,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    item_slot_query: Query<&ItemSlot>,
+    loadout_query: Query<&Loadout>,
+) {
+    let items: HashMap<ItemSlotId, Entity> = item_slot_query.iter()
+    .filter_map(|item_slot| item_slot.item.map(|item| (item_slot.id.clone(), item)))
+    .collect();
+
+    if items.is_empty() {
+        messages.write("Equip something before saving a loadout.".into());
+        return;
+    }
+
+    let name = next_loadout_name(&loadout_query);
+    messages.write(format!("Saved '{}'.", name).into());
+
+    commands.spawn(Loadout { name, items, action: None });
+}
+
+pub(super) fn on_equip_loadout_request(
+    event: On<EquipLoadoutRequest>,
+    commands: Commands,
+
+    loadout_query: Query<&Loadout>,
+    mut item_slot_query: Query<&mut ItemSlot>,
+    item_query: Query<&AffixiveItem>,
+) {
+    let loadout = loadout_query.get(event.loadout).expect("EquipLoadoutRequest event's loadout entity must have a Loadout component.");
+
+    equip_loadout(commands, loadout, &mut item_slot_query, &item_query);
+}
+
+pub(super) fn on_bind_loadout_request(
+    event: On<BindLoadoutRequest>,
+
+    mut loadout_query: Query<(Entity, &mut Loadout)>,
+) {
+    for (entity, mut loadout) in loadout_query.iter_mut() {
+        if entity == event.loadout {
+            loadout.action = event.action;
+        } else if event.action.is_some() && loadout.action == event.action {
+            loadout.action = None;
+        }
+    }
+}
+
+pub(super) fn on_delete_loadout_request(
+    event: On<DeleteLoadoutRequest>,
+    mut commands: Commands,
+) {
+    commands.entity(event.loadout).despawn();
+}
@@ -5,10 +5,27 @@ pub mod modifier;
 pub mod base;
 pub mod item_database;
 pub mod craft;
+pub mod definitions;
+pub mod currency;
+pub mod salvage;
+pub mod inventory_view;
+pub mod loadout;
+pub mod durability;
+pub mod item_set;
+pub mod rune;
 
 use bevy::prelude::*;
 
+use crate::incremental::IncrementalStartupSystemSet;
 use crate::incremental::item::item_database::ItemDatabase;
+use crate::incremental::stock::producer_consumer::{StockSystems, StockTick};
+
+/// Event for when the player gets an item other than by crafting it, such as a story reward.
+#[derive(Debug, Event)]
+pub struct ItemObtained {
+    /// Entity that contains the obtained [AffixiveItem](affixive_item::AffixiveItem) as a Component.
+    pub item: Entity,
+}
 
 pub struct ItemPlugin;
 
@@ -16,10 +33,38 @@ impl bevy::prelude::Plugin for ItemPlugin {
     fn build(&self, app: &mut App) {
         app
         .init_resource::<ItemDatabase>()
+        .init_resource::<inventory_view::InventoryView>()
 
         .add_plugins(craft::ItemCraftPlugin)
 
-        .add_observer(equipment::on_equip)
+        .add_systems(Startup, (item_slot::initialize_item_slots, rune::spawn_rune_recipes).in_set(IncrementalStartupSystemSet))
+        .add_systems(Update, (item_slot::update_item_slots, rune::update_rune_recipes))
+        .add_systems(StockTick, durability::wear_equipped_items.in_set(StockSystems::PostConsume))
+
+        .add_observer(currency::on_use_currency_request)
+        .add_observer(equipment::on_equip_request)
+        .add_observer(equipment::on_unequip_request)
+        .add_observer(item_slot::on_unlock_item_slot)
+        .add_observer(durability::on_repair_request)
+        .add_observer(salvage::on_discard_request)
+        .add_observer(salvage::on_salvage_request)
+        .add_observer(rune::on_craft_rune_request)
+        .add_observer(rune::on_socket_rune_request)
+        .add_observer(rune::on_unsocket_rune_request)
+        .add_observer(loadout::on_save_loadout_request)
+        .add_observer(loadout::on_equip_loadout_request)
+        .add_observer(loadout::on_bind_loadout_request)
+        .add_observer(loadout::on_delete_loadout_request)
         ;
+
+        // Headless runs have no asset server and only use the built in item definitions.
+        if app.is_plugin_added::<AssetPlugin>() {
+            app
+            .init_asset::<definitions::ItemDefinitions>()
+            .register_asset_loader(definitions::ItemDefinitionsLoader)
+            .add_systems(Startup, definitions::load_item_definitions)
+            .add_systems(Update, definitions::rebuild_item_database)
+            ;
+        }
     }
 }
\ No newline at end of file
@@ -1,10 +1,29 @@
 use std::ops::{Deref, DerefMut};
 
 use rand::Rng as _;
+use serde::{Deserialize, Serialize};
+
+use crate::incremental::stats::{StatId, StatModifier, StatOperation};
 
 pub type ModifierValue = i32;
 
-#[derive(Debug, Clone, Copy)]
+/// Stable identifier of an implicit, prefix, or suffix in the item definitions.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AffixId(pub String);
+
+impl std::fmt::Display for AffixId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Affixes in the same group cannot be on the same item.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ModifierGroup(pub String);
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct Modifier {
     pub kind: ModifierKind,
     pub min: ModifierValue,
@@ -21,18 +40,26 @@ impl Modifier {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Affix {
-    #[expect(unused)] // Have not implemented deeper inspection.
     pub name: String,
     pub modifier: Modifier,
     pub modifier_actual: ModifierValue,
     pub hybrid_modifier: Option<Modifier>,
     pub hybrid_modifier_actual: ModifierValue,
+
+    /// Which tier of its affix family this affix rolled, starting at 1.
+    /// Implicits have no tier.
+    #[serde(default)]
+    pub tier: Option<u8>,
+
+    /// Implicits have no group.
+    #[serde(default)]
+    pub group: Option<ModifierGroup>,
 }
 
 impl Affix {
-    /// Construct a new affix with the given modifier. Sets the hybrid to `None`.
+    /// Construct a new affix with the given modifier. Sets the hybrid, tier, and group to `None`.
     pub fn new(name: String, modifier: Modifier) -> Self {
         Self {
             name,
@@ -40,10 +67,11 @@ impl Affix {
             modifier_actual: if modifier.min == modifier.max { modifier.min } else { 0 },
             hybrid_modifier: None,
             hybrid_modifier_actual: 0,
+            tier: None,
+            group: None,
         }
     }
 
-    #[allow(unused, reason = "Debug function")]
     pub fn display(&self) -> String {
         let mut output = String::new();
 
@@ -71,7 +99,7 @@ impl Affix {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Implicit(pub Affix);
 
 impl Deref for Implicit {
@@ -88,7 +116,7 @@ impl DerefMut for Implicit {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Prefix(pub Affix);
 
 impl Deref for Prefix {
@@ -105,7 +133,7 @@ impl DerefMut for Prefix {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub(crate) struct Suffix(pub Affix);
 
 impl Deref for Suffix {
@@ -124,7 +152,7 @@ impl DerefMut for Suffix {
 
 // --- Actual modifiers below --- //
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum ModifierKind {
     /// Base amount of wood added to the stockyard per second when the player uses Gather Wood in hundredths
     WoodBase,
@@ -147,11 +175,83 @@ pub enum ModifierKind {
     ToolAffinityChanceMultiplier,
     ToolAffinityMultiplier,
     ToolAffinityTimeMultiplier,
+    /// Adds to both wood and stone gain after every multiplier by hundreds of the modifier's value
+    ToolAddedGain,
 
     HuntBase,
+    HuntBaseGain,
+    HuntMultiplier,
 }
 
 impl ModifierKind {
+    pub const LIST: &[Self] = &[
+        Self::WoodBase,
+        Self::WoodBaseGain,
+        Self::WoodMultiplier,
+        Self::WoodAffinityChanceMultiplier,
+        Self::WoodAffinityMultiplier,
+        Self::WoodAffinityTimeMultiplier,
+
+        Self::StoneBase,
+        Self::StoneBaseGain,
+        Self::StoneMultiplier,
+        Self::StoneAffinityChanceMultiplier,
+        Self::StoneAffinityMultiplier,
+        Self::StoneAffinityTimeMultiplier,
+
+        Self::ToolMultiplier,
+        Self::ToolAffinityChanceMultiplier,
+        Self::ToolAffinityMultiplier,
+        Self::ToolAffinityTimeMultiplier,
+        Self::ToolAddedGain,
+
+        Self::HuntBase,
+        Self::HuntBaseGain,
+        Self::HuntMultiplier,
+    ];
+
+    /// The stats the modifier kind affects and how.
+    pub fn stat_effects(self) -> &'static [(StatId, StatOperation)] {
+        use StatOperation::{Added, Base, Increased, Offset};
+
+        match self {
+            ModifierKind::WoodBase => &[(StatId::WoodGain, Base)],
+            ModifierKind::WoodBaseGain => &[(StatId::WoodGain, Offset)],
+            ModifierKind::WoodMultiplier => &[(StatId::WoodGain, Increased)],
+            ModifierKind::WoodAffinityChanceMultiplier => &[(StatId::WoodAffinityChance, Increased)],
+            ModifierKind::WoodAffinityMultiplier => &[(StatId::WoodAffinityMultiplier, Increased)],
+            ModifierKind::WoodAffinityTimeMultiplier => &[(StatId::WoodAffinityTime, Increased)],
+
+            ModifierKind::StoneBase => &[(StatId::StoneGain, Base)],
+            ModifierKind::StoneBaseGain => &[(StatId::StoneGain, Offset)],
+            ModifierKind::StoneMultiplier => &[(StatId::StoneGain, Increased)],
+            ModifierKind::StoneAffinityChanceMultiplier => &[(StatId::StoneAffinityChance, Increased)],
+            ModifierKind::StoneAffinityMultiplier => &[(StatId::StoneAffinityMultiplier, Increased)],
+            ModifierKind::StoneAffinityTimeMultiplier => &[(StatId::StoneAffinityTime, Increased)],
+
+            ModifierKind::ToolMultiplier => &[(StatId::WoodGain, Increased), (StatId::StoneGain, Increased)],
+            ModifierKind::ToolAffinityChanceMultiplier => &[(StatId::WoodAffinityChance, Increased), (StatId::StoneAffinityChance, Increased)],
+            ModifierKind::ToolAffinityMultiplier => &[(StatId::WoodAffinityMultiplier, Increased), (StatId::StoneAffinityMultiplier, Increased)],
+            ModifierKind::ToolAffinityTimeMultiplier => &[(StatId::WoodAffinityTime, Increased), (StatId::StoneAffinityTime, Increased)],
+            ModifierKind::ToolAddedGain => &[(StatId::WoodGain, Added), (StatId::StoneGain, Added)],
+
+            ModifierKind::HuntBase => &[(StatId::HuntGain, Base)],
+            ModifierKind::HuntBaseGain => &[(StatId::HuntGain, Offset)],
+            ModifierKind::HuntMultiplier => &[(StatId::HuntGain, Increased)],
+        }
+    }
+
+    /// The stat modifiers for a modifier of this kind with the value.
+    ///
+    /// Bases, offsets and flat additions are in hundredths and increases are in percent.
+    pub fn stat_modifiers(self, value: ModifierValue) -> impl Iterator<Item = StatModifier> {
+        self.stat_effects().iter().map(move |&(stat, operation)| StatModifier {
+            stat,
+            operation,
+            value: value as f64 / 100.0,
+        })
+    }
+
     pub fn display_actual(&self, actual: i32) -> String {
         fn sign(n: i32) -> char {
             if n > 0 { '+' } else { '-' }
@@ -180,52 +280,41 @@ impl ModifierKind {
             ModifierKind::ToolAffinityChanceMultiplier => format!("{}{} Tool action affinity chance", sign(actual), percent(actual)),
             ModifierKind::ToolAffinityMultiplier => format!("{}{}% affinity multiplier for tool actions", sign(actual), percent(actual)),
             ModifierKind::ToolAffinityTimeMultiplier => format!("{}{}% affinity time multiplier for tool actions", sign(actual), percent(actual)),
+            ModifierKind::ToolAddedGain => format!("{}{} Wood and stone per second after multipliers", sign(actual), percent(actual)),
 
             ModifierKind::HuntBase => format!("Hunting produces {} carcasses per second", percent(actual)),
+            ModifierKind::HuntBaseGain => format!("{}{} Carcasses hunted per second", sign(actual), percent(actual)),
+            ModifierKind::HuntMultiplier => format!("{}{}% Carcasses hunted per second", sign(actual), actual),
         }
     }
 }
 
-pub(crate) fn initialize_implicits() -> Vec<Implicit> {
-    let mods = vec![
-        Affix::new("Tier0ToolsGatherWoodBase".to_string(), Modifier { kind: ModifierKind::WoodBase, min: 20, max: 20 }),
-        Affix::new("Tier0ToolsGatherStoneBase".to_string(), Modifier { kind: ModifierKind::StoneBase, min: 50, max: 50 }),
-        Affix::new("Tier1ToolsGatherWoodBase".to_string(), Modifier { kind: ModifierKind::WoodBase, min: 80, max: 120 }),
-        Affix::new("Tier1ToolsGatherStoneBase".to_string(), Modifier { kind: ModifierKind::StoneBase, min: 80, max: 120 }),
-        Affix::new("Tier0Bow".to_string(), Modifier { kind: ModifierKind::HuntBase, min: 15, max: 25 }),
-    ];
-
-    mods.into_iter().map(Implicit).collect()
-}
-
-pub(crate) fn initialize_prefixes() -> Vec<Prefix> {
-    let mods = vec![
-        Affix::new("Lumberjack's".to_string(), Modifier { kind: ModifierKind::WoodBaseGain, min: 10, max: 20 }),
-        Affix::new("Tree Feller's".to_string(), Modifier { kind: ModifierKind::WoodMultiplier, min: 10, max: 20 }),
-        Affix::new("'s".to_string(), Modifier { kind: ModifierKind::WoodAffinityMultiplier, min: 50, max: 100 }),
-
-        Affix::new("'s".to_string(), Modifier { kind: ModifierKind::StoneBaseGain, min: 10, max: 20 }),
-        Affix::new("'s".to_string(), Modifier { kind: ModifierKind::StoneMultiplier, min: 10, max: 20 }),
-        Affix::new("'s".to_string(), Modifier { kind: ModifierKind::StoneAffinityMultiplier, min: 50, max: 100 }),
-
-        Affix::new("'s".to_string(), Modifier { kind: ModifierKind::ToolMultiplier, min: 5, max: 15 })
-    ];
-
-    mods.into_iter().map(Prefix).collect()
+impl std::fmt::Display for ModifierKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            ModifierKind::WoodBase => "Base wood",
+            ModifierKind::WoodBaseGain => "Added wood",
+            ModifierKind::WoodMultiplier => "Increased wood",
+            ModifierKind::WoodAffinityChanceMultiplier => "Wood affinity chance",
+            ModifierKind::WoodAffinityMultiplier => "Wood affinity gain",
+            ModifierKind::WoodAffinityTimeMultiplier => "Wood affinity time",
+
+            ModifierKind::StoneBase => "Base stone",
+            ModifierKind::StoneBaseGain => "Added stone",
+            ModifierKind::StoneMultiplier => "Increased stone",
+            ModifierKind::StoneAffinityChanceMultiplier => "Stone affinity chance",
+            ModifierKind::StoneAffinityMultiplier => "Stone affinity gain",
+            ModifierKind::StoneAffinityTimeMultiplier => "Stone affinity time",
+
+            ModifierKind::ToolMultiplier => "Increased tool resources",
+            ModifierKind::ToolAffinityChanceMultiplier => "Tool affinity chance",
+            ModifierKind::ToolAffinityMultiplier => "Tool affinity multiplier",
+            ModifierKind::ToolAffinityTimeMultiplier => "Tool affinity time",
+            ModifierKind::ToolAddedGain => "Flat tool resources",
+
+            ModifierKind::HuntBase => "Base carcasses",
+            ModifierKind::HuntBaseGain => "Added carcasses",
+            ModifierKind::HuntMultiplier => "Increased carcasses",
+        })
+    }
 }
-
-pub(crate) fn initialize_suffixes() -> Vec<Suffix> {
-    let mods = vec![
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::WoodAffinityChanceMultiplier, min: 50, max: 100 }),
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::WoodAffinityTimeMultiplier, min: 100, max: 200 }),
-
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::StoneAffinityChanceMultiplier, min: 50, max: 100 }),
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::StoneAffinityTimeMultiplier, min: 100, max: 200 }),
-
-        Affix::new("ingenuity".to_string(), Modifier { kind: ModifierKind::ToolAffinityChanceMultiplier, min: 25, max: 50 }),
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::ToolAffinityMultiplier, min: 30, max: 70 }),
-        Affix::new("".to_string(), Modifier { kind: ModifierKind::ToolAffinityTimeMultiplier, min: 75, max: 150 }),
-    ];
-
-    mods.into_iter().map(Suffix).collect()
-}
\ No newline at end of file
@@ -0,0 +1,302 @@
+//! Runes carry a single modifier and are socketed into items.
+//!
+//! Each rune in the item definitions can be crafted from stocks with its [RuneRecipe].
+//! A socketed rune becomes part of the item. Taking it out again costs stocks,
+//! or shatters the rune for free.
+
+use bevy::prelude::*;
+use itertools::Itertools as _;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+use crate::incremental::item::{affixive_item::AffixiveItem, definitions::{RuneDefinition, RuneId}, item_database::ItemDatabase, modifier::{Affix, Modifier}};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::{StockKind, stockyard::Stockyard};
+
+/// Fraction of a rune's crafting cost it takes to take the rune out of an item intact.
+const UNSOCKET_RECIPE_FRACTION: f64 = 0.5;
+
+#[derive(Debug, Clone, Component, Serialize, Deserialize)]
+pub struct Rune {
+    pub id: RuneId,
+
+    /// The rune's modifier, named after the rune.
+    pub affix: Affix,
+}
+
+impl Rune {
+    /// Construct the rune with its value rolled.
+    pub fn new(definition: &RuneDefinition) -> Self {
+        let mut affix = Affix::new(definition.name.clone(), definition.modifier);
+        affix.randomize_actual();
+
+        Self { id: definition.id.clone(), affix }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.affix.name
+    }
+
+    pub fn display(&self) -> String {
+        self.affix.display()
+    }
+}
+
+/// Description of how to craft a rune.
+///
+/// There is one for each rune in the item definitions.
+#[derive(Debug, Clone, Component)]
+pub struct RuneRecipe {
+    pub rune: RuneId,
+    pub name: String,
+    pub modifier: Modifier,
+
+    // Cost in stock resources to craft the rune
+    pub resources: SmallVec<[(StockKind, f64); 2]>,
+}
+
+impl From<&RuneDefinition> for RuneRecipe {
+    fn from(definition: &RuneDefinition) -> Self {
+        Self {
+            rune: definition.id.clone(),
+            name: definition.name.clone(),
+            modifier: definition.modifier,
+            resources: definition.resources.clone(),
+        }
+    }
+}
+
+/// Event to fire when the player tries to craft a rune.
+#[derive(Debug, Event)]
+pub struct CraftRuneRequest {
+    pub recipe: Entity,
+}
+
+/// Event to fire when the player tries to socket a rune into an item.
+#[derive(Debug, Event)]
+pub struct SocketRuneRequest {
+    /// Entity with the [AffixiveItem] to socket the rune into.
+    pub item: Entity,
+
+    /// Entity with the [Rune] to socket.
+    pub rune: Entity,
+}
+
+/// Event to fire when the player takes a rune out of an item.
+#[derive(Debug, Event)]
+pub struct UnsocketRuneRequest {
+    /// Entity with the [AffixiveItem] to take the rune out of.
+    pub item: Entity,
+
+    /// Index of the socket, starting at 0.
+    pub socket: usize,
+
+    /// Destroy the rune instead of paying to keep it.
+    pub shatter: bool,
+}
+
+/// Stocks it takes to take the rune out of an item intact.
+pub fn unsocket_cost(recipe: &RuneRecipe) -> SmallVec<[(StockKind, f64); 2]> {
+    recipe.resources.iter()
+    .map(|&(stock_kind, amount)| (stock_kind, (amount * UNSOCKET_RECIPE_FRACTION * 10.0).ceil() / 10.0))
+    .filter(|&(_, amount)| amount > 0.0)
+    .collect()
+}
+
+pub(super) fn spawn_rune_recipes(
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+) {
+    commands.spawn_batch(item_db.runes().iter().map(RuneRecipe::from).collect::<Vec<_>>());
+}
+
+/// Replaces the rune recipes when the item definitions are reloaded.
+pub(super) fn update_rune_recipes(
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+
+    rune_recipe_query: Query<Entity, With<RuneRecipe>>,
+) {
+    if !item_db.is_changed() || item_db.is_added() {
+        return;
+    }
+
+    for recipe_entity in rune_recipe_query.iter() {
+        commands.entity(recipe_entity).despawn();
+    }
+
+    commands.spawn_batch(item_db.runes().iter().map(RuneRecipe::from).collect::<Vec<_>>());
+}
+
+pub(super) fn on_craft_rune_request(
+    event: On<CraftRuneRequest>,
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+    mut stockyard: ResMut<Stockyard>,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    rune_recipe_query: Query<&RuneRecipe>,
+) {
+    let recipe = rune_recipe_query.get(event.recipe).expect("CraftRuneRequest event's recipe entity must have a RuneRecipe component.");
+
+    let Some(definition) = item_db.runes().iter().find(|definition| definition.id == recipe.rune) else {
+        warn!("Rune recipe for unknown rune `{}`.", recipe.rune);
+        return;
+    };
+
+    let has_sufficient_stock = recipe.resources.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount);
+
+    if !has_sufficient_stock {
+        messages.write(format!("Unable to craft {}. Insufficient resources.", recipe.name).into());
+        return;
+    }
+
+    for &(stock_kind, amount) in recipe.resources.iter() {
+        stockyard[stock_kind] -= amount;
+    }
+
+    let rune = Rune::new(definition);
+    messages.write(format!("Crafted '{}'", rune.name()).into());
+
+    commands.spawn(rune);
+}
+
+pub(super) fn on_socket_rune_request(
+    event: On<SocketRuneRequest>,
+    mut commands: Commands,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_query: Query<&mut AffixiveItem>,
+    rune_query: Query<&Rune>,
+) {
+    let mut item = item_query.get_mut(event.item).expect("SocketRuneRequest event's item entity must have an AffixiveItem component.");
+    let rune = rune_query.get(event.rune).expect("SocketRuneRequest event's rune entity must have a Rune component.");
+
+    if let Err(error) = item.try_socket(rune.clone()) {
+        messages.write(error.to_string().into());
+        return;
+    }
+
+    messages.write(format!("Socketed '{}' into '{}'.", rune.name(), item.name()).into());
+    commands.entity(event.rune).despawn();
+}
+
+pub(super) fn on_unsocket_rune_request(
+    event: On<UnsocketRuneRequest>,
+    mut commands: Commands,
+
+    mut stockyard: ResMut<Stockyard>,
+    mut messages: MessageWriter<LogEntry>,
+
+    mut item_query: Query<&mut AffixiveItem>,
+    rune_recipe_query: Query<&RuneRecipe>,
+) {
+    let mut item = item_query.get_mut(event.item).expect("UnsocketRuneRequest event's item entity must have an AffixiveItem component.");
+
+    let Some(rune) = item.runes().get(event.socket) else {
+        messages.write(format!("'{}' has no rune in that socket.", item.name()).into());
+        return;
+    };
+
+    if event.shatter {
+        messages.write(format!("Shattered '{}' taking it out of '{}'.", rune.name(), item.name()).into());
+        item.unsocket(event.socket);
+        return;
+    }
+
+    // Without a recipe there is nothing to price keeping the rune with. It can still be shattered.
+    let Some(recipe) = rune_recipe_query.iter().find(|recipe| recipe.rune == rune.id) else {
+        messages.write(format!("Unable to take '{}' out of '{}' intact. There is no recipe for it.", rune.name(), item.name()).into());
+        return;
+    };
+
+    let cost = unsocket_cost(recipe);
+
+    let has_sufficient_stock = cost.iter().all(|&(stock_kind, amount)| stockyard[stock_kind] >= amount);
+
+    if !has_sufficient_stock {
+        messages.write(format!("Unable to take '{}' out of '{}'. Insufficient resources.", rune.name(), item.name()).into());
+        return;
+    }
+
+    for &(stock_kind, amount) in cost.iter() {
+        stockyard[stock_kind] -= amount;
+    }
+
+    let cost_text = cost.iter().map(|&(stock_kind, amount)| format!("{} - {}", stock_kind, amount)).join("  ");
+    messages.write(format!("Took '{}' out of '{}' for {}.", rune.name(), item.name(), cost_text).into());
+
+    let rune = item.unsocket(event.socket).expect("The socket was checked to have a rune.");
+    commands.spawn(rune);
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::affixive_item::{AffixiveItem, SocketRuneError};
+    use crate::incremental::item::base::Base;
+    use crate::incremental::item::definitions::ArtifactId;
+    use crate::incremental::item::item_database::ItemDatabase;
+    use crate::incremental::story::REMEMBERED_ARTIFACT;
+
+    use super::Rune;
+
+    fn rune(item_db: &ItemDatabase, id: &str) -> Rune {
+        let definition = item_db.runes().iter().find(|definition| definition.id.0 == id)
+        .expect("Shipped item definitions must have the rune.");
+
+        Rune::new(definition)
+    }
+
+    #[test]
+    fn runes_fill_sockets_and_come_back_out() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::StoneTools);
+        let felling = rune(&item_db, "bone_felling_rune");
+
+        assert!(item.try_socket(felling.clone()).is_ok());
+        assert!(matches!(item.try_socket(rune(&item_db, "bone_quarrying_rune")), Err(SocketRuneError::SocketsFull)));
+        assert_eq!(item.runes().len(), 1);
+
+        assert!(item.unsocket(1).is_none());
+        let unsocketed = item.unsocket(0).expect("The socketed rune must come back out.");
+        assert_eq!(unsocketed.id, felling.id);
+        assert!(item.runes().is_empty());
+    }
+
+    #[test]
+    fn runes_need_an_open_socket_on_a_craftable_item() {
+        let item_db = ItemDatabase::new();
+
+        let mut makeshift_tools = item_db.create_test_item(Base::MakeshiftTools);
+        assert!(matches!(makeshift_tools.try_socket(rune(&item_db, "diamond_rune")), Err(SocketRuneError::NoSockets)));
+
+        let mut artifact = item_db.create_artifact(&ArtifactId(REMEMBERED_ARTIFACT.into()))
+        .expect("Shipped item definitions must have the story's artifact.");
+        assert!(matches!(artifact.try_socket(rune(&item_db, "diamond_rune")), Err(SocketRuneError::AffixiveItemIsFixed)));
+        assert!(artifact.runes().is_empty());
+    }
+
+    #[test]
+    fn socketed_runes_add_their_modifier_to_the_item() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::StoneTools);
+        let felling = rune(&item_db, "bone_felling_rune");
+
+        let rune_modifiers = |item: &AffixiveItem| item.modifiers()
+        .filter(|(name, _, _)| name.as_ref() == felling.name())
+        .map(|(_, modifier, value)| (modifier.kind, value))
+        .collect::<Vec<_>>();
+
+        assert!(rune_modifiers(&item).is_empty());
+
+        item.try_socket(felling.clone()).expect("Stone tools must have an open socket.");
+
+        let expected = felling.affix.modifiers().map(|(modifier, value)| (modifier.kind, value)).collect::<Vec<_>>();
+        assert_eq!(rune_modifiers(&item), expected);
+    }
+}
@@ -0,0 +1,159 @@
+//! Getting rid of unwanted items, either by discarding them or salvaging them for stocks.
+//!
+//! Runes socketed into the item are returned to the inventory either way.
+
+use bevy::prelude::*;
+use itertools::Itertools as _;
+use smallvec::SmallVec;
+
+use crate::incremental::item::{affixive_item::AffixiveItem, craft::Recipe, currency::CraftingCurrency, item_slot::ItemSlot};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::{StockKind, stockyard::Stockyard};
+
+/// Fraction of the item's recipe cost returned by salvaging it.
+const SALVAGE_RECIPE_FRACTION: f64 = 0.25;
+
+/// Fraction of the cost of an [Add Affix](CraftingCurrency::AddAffix) returned for each prefix and suffix.
+const SALVAGE_AFFIX_FRACTION: f64 = 0.5;
+
+/// Event to fire when the player throws away an item.
+#[derive(Debug, Event)]
+pub struct DiscardRequest {
+    /// Entity with the [AffixiveItem] to discard.
+    pub item: Entity,
+}
+
+/// Event to fire when the player breaks down an item for some of its stocks back.
+#[derive(Debug, Event)]
+pub struct SalvageRequest {
+    /// Entity with the [AffixiveItem] to salvage.
+    pub item: Entity,
+}
+
+/// Stocks returned by salvaging the item.
+///
+/// A fraction of the recipe for the item's base, plus a fraction of the
+/// cost of adding an affix for each of its prefixes and suffixes.
+/// Socketed runes are returned instead of salvaged, so they add nothing.
+pub fn salvage_value(item: &AffixiveItem, recipe: Option<&Recipe>) -> SmallVec<[(StockKind, f64); 2]> {
+    let recipe_value = recipe.into_iter()
+    .flat_map(|recipe| recipe.resources.iter())
+    .map(|&(stock_kind, amount)| (stock_kind, amount * SALVAGE_RECIPE_FRACTION));
+
+    let affix_value = CraftingCurrency::AddAffix.cost().into_iter()
+    .map(|(stock_kind, amount)| (stock_kind, amount * SALVAGE_AFFIX_FRACTION * item.affix_count() as f64));
+
+    recipe_value.chain(affix_value)
+    .into_grouping_map()
+    .sum()
+    .into_iter()
+    .filter(|&(_, amount)| amount > 0.0)
+    .sorted_by_key(|&(stock_kind, _)| stock_kind)
+    .collect()
+}
+
+/// Returns the runes socketed into the item to the inventory before the item is gone.
+fn return_runes(mut commands: Commands, messages: &mut MessageWriter<LogEntry>, item: &AffixiveItem) {
+    for rune in item.runes() {
+        messages.write(format!("Took '{}' out of '{}'.", rune.name(), item.name()).into());
+        commands.spawn(rune.clone());
+    }
+}
+
+pub(super) fn on_discard_request(
+    event: On<DiscardRequest>,
+    mut commands: Commands,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    item_query: Query<&AffixiveItem>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let item = item_query.get(event.item).expect("DiscardRequest event's item entity must have an AffixiveItem component.");
+
+    if item_slot_query.iter().any(|item_slot| item_slot.item == Some(event.item)) {
+        messages.write(format!("Unequip '{}' before discarding it.", item.name()).into());
+        return;
+    }
+
+    return_runes(commands.reborrow(), &mut messages, item);
+
+    messages.write(format!("Discarded '{}'.", item.name()).into());
+    commands.entity(event.item).despawn();
+}
+
+pub(super) fn on_salvage_request(
+    event: On<SalvageRequest>,
+    mut commands: Commands,
+
+    mut stockyard: ResMut<Stockyard>,
+    mut messages: MessageWriter<LogEntry>,
+
+    item_query: Query<&AffixiveItem>,
+    item_slot_query: Query<&ItemSlot>,
+    recipe_query: Query<&Recipe>,
+) {
+    let item = item_query.get(event.item).expect("SalvageRequest event's item entity must have an AffixiveItem component.");
+
+    if item_slot_query.iter().any(|item_slot| item_slot.item == Some(event.item)) {
+        messages.write(format!("Unequip '{}' before salvaging it.", item.name()).into());
+        return;
+    }
+
+    let recipe = recipe_query.iter().find(|recipe| recipe.base == item.base());
+    let value = salvage_value(item, recipe);
+
+    for &(stock_kind, amount) in value.iter() {
+        stockyard[stock_kind] += amount;
+    }
+
+    let value_text = if value.is_empty() {
+        "nothing".to_string()
+    } else {
+        value.iter().map(|&(stock_kind, amount)| format!("{} - {}", stock_kind, amount)).join("  ")
+    };
+
+    return_runes(commands.reborrow(), &mut messages, item);
+
+    messages.write(format!("Salvaged '{}' for {}.", item.name(), value_text).into());
+    commands.entity(event.item).despawn();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::{affixive_item::ItemLevel, base::Base, craft::Recipe, item_database::ItemDatabase};
+    use crate::incremental::stock::StockKind;
+
+    use super::salvage_value;
+
+    #[test]
+    fn salvaging_returns_part_of_the_recipe_and_of_each_affix() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::StoneTools);
+        let recipe = Recipe {
+            base: Base::StoneTools,
+            resources: smallvec::smallvec![(StockKind::Wood, 8.0), (StockKind::Stone, 4.0)],
+            item_level: ItemLevel(1),
+        };
+
+        assert_eq!(salvage_value(&item, Some(&recipe)).as_slice(), &[(StockKind::Wood, 2.0), (StockKind::Stone, 1.0)]);
+
+        item_db.try_push_random_prefix(&mut item).expect("Stone tools must be able to roll a prefix.");
+        item_db.try_push_random_suffix(&mut item).expect("Stone tools must be able to roll a suffix.");
+
+        // Adding an affix costs 10 stone, and half of that comes back for each.
+        assert_eq!(salvage_value(&item, Some(&recipe)).as_slice(), &[(StockKind::Wood, 2.0), (StockKind::Stone, 11.0)]);
+    }
+
+    #[test]
+    fn items_without_a_recipe_only_return_their_affixes() {
+        let item_db = ItemDatabase::new();
+        let mut item = item_db.create_test_item(Base::StoneTools);
+
+        assert!(salvage_value(&item, None).is_empty());
+
+        item_db.try_push_random_prefix(&mut item).expect("Stone tools must be able to roll a prefix.");
+
+        assert_eq!(salvage_value(&item, None).as_slice(), &[(StockKind::Stone, 5.0)]);
+    }
+}
@@ -1,9 +1,10 @@
 //! Follower jobs
 
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::incremental::stock::StockPerSecond;
-use crate::incremental::stock::producer_consumer::StockSystems;
+use crate::incremental::stock::producer_consumer::{StockSystems, StockTick};
 use crate::incremental::stock::{StockKind, producer_consumer::StockyardProducerConsumer, stockyard::Stockyard};
 use crate::incremental::{IncrementalStartupSystemSet, PerSecond};
 
@@ -16,7 +17,7 @@ impl Plugin for JobsPlugin {
         app
         .init_resource::<FollowersAssigned>()
         .add_systems(Startup, initialize_jobs.in_set(IncrementalStartupSystemSet))
-        .add_systems(FixedUpdate, (
+        .add_systems(StockTick, (
             spc::preconsume.in_set(StockSystems::PreConsume),
             spc::postconsume.in_set(StockSystems::PostConsume),
         ))
@@ -26,7 +27,7 @@ impl Plugin for JobsPlugin {
     }
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component)]
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, Component, Serialize, Deserialize)]
 pub enum JobKind {
     #[default]
     ChopWood,
@@ -93,9 +94,9 @@ impl std::fmt::Display for JobKind {
 }
 
 #[derive(Debug, Component)]
-struct Job {
-    kind: JobKind,
-    followers_assigned: u32,
+pub(crate) struct Job {
+    pub(crate) kind: JobKind,
+    pub(crate) followers_assigned: u32,
     produces: Vec<StockPerSecond>,
     consumes: Vec<StockPerSecond>,
 }
@@ -124,7 +125,7 @@ fn initialize_jobs(
 /// The total number of followers assigned to jobs.
 /// 
 /// This must always be less than or equal to the number of followers in the stockyard.
-struct FollowersAssigned(u32);
+pub(crate) struct FollowersAssigned(u32);
 
 /// Event for the UI to trigger when the user tries to assign a follower.
 #[derive(Debug, Event)]
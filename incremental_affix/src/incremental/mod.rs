@@ -4,7 +4,6 @@ use std::fmt::Display;
 use bevy::prelude::*;
 
 use item::item_database::ItemDatabase;
-use stats::PlayerActionsStats;
 
 pub mod action;
 pub mod stats;
@@ -14,6 +13,7 @@ pub mod affinity;
 pub mod job;
 pub mod story;
 pub mod log;
+pub mod save;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
 pub struct IncrementalStartupSystemSet;
@@ -27,7 +27,6 @@ impl IncrementalPlugin {
 impl Plugin for IncrementalPlugin {
     fn build(&self, app: &mut bevy::prelude::App) {
         app
-        .init_resource::<PlayerActionsStats>()
         .init_resource::<ItemDatabase>()
         .insert_resource(TickTimer(Timer::from_seconds(const { 1.0 / Self::TICKS_PER_SECOND }, TimerMode::Repeating)))
 
@@ -37,6 +36,7 @@ impl Plugin for IncrementalPlugin {
             action::ActionPlugin,
             stock::StockPlugin,
             item::ItemPlugin,
+            stats::StatsPlugin,
             job::JobsPlugin,
         ))
 
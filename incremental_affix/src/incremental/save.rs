@@ -0,0 +1,381 @@
+//! Saving the game to disk and restoring it on startup.
+//!
+//! The save file is the [SaveGame] struct written out as RON.
+//! Bump [SAVE_VERSION] whenever the format changes.
+
+use std::fmt::{Display, Write as _};
+use std::time::{Duration, SystemTime};
+
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::time::common_conditions::on_real_timer;
+use serde::{Deserialize, Serialize};
+
+use crate::incremental::IncrementalStartupSystemSet;
+use crate::incremental::action::{Action, ActionProgress, ChangeAction, CurrentAction, KnownActions};
+use crate::incremental::item::{affixive_item::AffixiveItem, craft::{BenchRecipe, Recipe}, equipment::Equipped, item_slot::{ItemSlot, ItemSlotId}, loadout::Loadout, rune::Rune};
+use crate::incremental::job::{FollowersAssigned, Job, JobKind};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::{StockKind, on_total::OnStockTotalProduced, producer_consumer::fast_forward_stocks, stockyard::Stockyard};
+use crate::incremental::story::{StoryObservers, StoryStage};
+
+/// Version of the save format written by this build.
+///
+/// Saves with a newer version than this are refused.
+pub const SAVE_VERSION: u32 = 1;
+
+/// Location of the save file relative to the working directory.
+const SAVE_PATH: &str = "save.ron";
+
+const AUTOSAVE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// System set for restoring the saved game.
+///
+/// Runs after [IncrementalStartupSystemSet] has spawned a new game's entities,
+/// and overwrites them with the saved state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
+pub struct RestoreSystemSet;
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .configure_sets(Startup, RestoreSystemSet.after(IncrementalStartupSystemSet))
+        .add_systems(Startup, restore_game.in_set(RestoreSystemSet))
+        .add_systems(Update, save_game.run_if(on_real_timer(AUTOSAVE_INTERVAL)))
+        .add_systems(Last, save_game.run_if(on_message::<AppExit>))
+        ;
+    }
+}
+
+/// Everything about a game that is written to disk.
+#[derive(Debug, Serialize, Deserialize)]
+struct SaveGame {
+    version: u32,
+
+    /// Seconds since the Unix epoch when the game was saved.
+    saved_at: u64,
+
+    stocks: Vec<SavedStock>,
+
+    known_actions: Vec<Action>,
+    current_action: Option<Action>,
+    action_progress: f32,
+
+    items: Vec<AffixiveItem>,
+    item_slots: Vec<SavedItemSlot>,
+    recipes: Vec<Recipe>,
+
+    bench_recipes: Vec<BenchRecipe>,
+
+    loadouts: Vec<SavedLoadout>,
+
+    /// Runes that are not socketed into an item.
+    runes: Vec<Rune>,
+
+    jobs: Vec<(JobKind, u32)>,
+
+    story_stage: StoryStage,
+    story_count: u32,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedStock {
+    kind: StockKind,
+    current: f64,
+    maximum: Option<f64>,
+    total_produced: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedItemSlot {
+    slot: ItemSlotId,
+
+    /// Index into [SaveGame::items].
+    item: Option<usize>,
+
+    unlocked: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct SavedLoadout {
+    name: String,
+
+    /// Indices into [SaveGame::items].
+    items: Vec<(ItemSlotId, usize)>,
+
+    action: Option<Action>,
+}
+
+#[derive(Debug)]
+enum LoadError {
+    Io(std::io::Error),
+    Parse(ron::error::SpannedError),
+    UnsupportedVersion(u32),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(error) => write!(f, "Unable to read save file: {}", error),
+            LoadError::Parse(error) => write!(f, "Save file is malformed: {}", error),
+            LoadError::UnsupportedVersion(version) => write!(f, "Save file version {} is newer than the supported version {}", version, SAVE_VERSION),
+        }
+    }
+}
+
+/// Read the save file. Returns `Ok(None)` when there is no save file.
+fn read_save() -> Result<Option<SaveGame>, LoadError> {
+    let contents = match std::fs::read_to_string(SAVE_PATH) {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(error) => return Err(LoadError::Io(error)),
+    };
+
+    let save: SaveGame = ron::from_str(&contents).map_err(LoadError::Parse)?;
+
+    if save.version > SAVE_VERSION {
+        return Err(LoadError::UnsupportedVersion(save.version));
+    }
+
+    Ok(Some(save))
+}
+
+fn save_game(
+    stockyard: Res<Stockyard>,
+    known_actions: Res<KnownActions>,
+    current_action: Res<CurrentAction>,
+    action_progress: Res<ActionProgress>,
+
+    story: Single<&StoryObservers>,
+
+    item_query: Query<(Entity, &AffixiveItem)>,
+    item_slot_query: Query<&ItemSlot>,
+    recipe_query: Query<&Recipe>,
+    bench_recipe_query: Query<&BenchRecipe>,
+    loadout_query: Query<&Loadout>,
+    rune_query: Query<&Rune>,
+    job_query: Query<&Job>,
+) {
+    let mut items = vec![];
+    let mut item_indices = HashMap::new();
+
+    for (index, (entity, item)) in item_query.iter().enumerate() {
+        items.push(item.clone());
+        item_indices.insert(entity, index);
+    }
+
+    let save = SaveGame {
+        version: SAVE_VERSION,
+        saved_at: unix_time_now().as_secs(),
+
+        stocks: stockyard.iter().map(|(&kind, stock)| SavedStock {
+            kind,
+            current: stock.current(),
+            maximum: stock.maximum(),
+            total_produced: stock.total_produced(),
+        }).collect(),
+
+        known_actions: known_actions.iter().copied().collect(),
+        current_action: **current_action,
+        action_progress: action_progress.percent,
+
+        items,
+        item_slots: item_slot_query.iter().map(|item_slot| SavedItemSlot {
+            slot: item_slot.id.clone(),
+            item: item_slot.item.and_then(|item| item_indices.get(&item).copied()),
+            unlocked: item_slot.unlocked,
+        }).collect(),
+        recipes: recipe_query.iter().cloned().collect(),
+        bench_recipes: bench_recipe_query.iter().cloned().collect(),
+        loadouts: loadout_query.iter().map(|loadout| SavedLoadout {
+            name: loadout.name.clone(),
+            items: loadout.items.iter()
+            .filter_map(|(slot_id, item)| item_indices.get(item).map(|&index| (slot_id.clone(), index)))
+            .collect(),
+            action: loadout.action,
+        }).collect(),
+        runes: rune_query.iter().cloned().collect(),
+
+        jobs: job_query.iter().map(|job| (job.kind, job.followers_assigned)).collect(),
+
+        story_stage: story.stage,
+        story_count: story.count,
+    };
+
+    let contents = match ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::default()) {
+        Ok(contents) => contents,
+        Err(error) => {
+            error!("Unable to serialize the game: {}", error);
+            return;
+        }
+    };
+
+    if let Err(error) = std::fs::write(SAVE_PATH, contents) {
+        error!("Unable to write save file: {}", error);
+    }
+}
+
+fn restore_game(
+    mut commands: Commands,
+
+    mut stockyard: ResMut<Stockyard>,
+    mut known_actions: ResMut<KnownActions>,
+    mut followers_assigned: ResMut<FollowersAssigned>,
+
+    mut story: Single<&mut StoryObservers>,
+
+    mut item_slot_query: Query<&mut ItemSlot>,
+    mut job_query: Query<&mut Job>,
+    recipe_query: Query<Entity, With<Recipe>>,
+    bench_recipe_query: Query<Entity, With<BenchRecipe>>,
+    on_total_query: Query<(Entity, &OnStockTotalProduced)>,
+) {
+    let save = match read_save() {
+        Ok(Some(save)) => save,
+        Ok(None) => return,
+        Err(error) => {
+            error!("{}", error);
+            return;
+        }
+    };
+
+    for saved_stock in save.stocks.iter() {
+        stockyard[saved_stock.kind].restore(saved_stock.current, saved_stock.maximum, saved_stock.total_produced);
+    }
+
+    // Stock totals that were already reached have already had their effect applied.
+    for (entity, on_total) in on_total_query.iter() {
+        if stockyard[on_total.stock_kind].total_produced() >= on_total.total_produced {
+            commands.entity(entity).despawn();
+        }
+    }
+
+    known_actions.clear();
+    known_actions.extend(save.known_actions.iter().copied());
+
+    let items: Vec<Entity> = save.items.into_iter()
+    .map(|item| commands.spawn(item).id())
+    .collect();
+
+    let mut equipped_item = None;
+
+    // Items of slots that are no longer in the item definitions stay in the inventory.
+    for saved_slot in save.item_slots.iter() {
+        let Some(mut item_slot) = item_slot_query.iter_mut().find(|item_slot| item_slot.id == saved_slot.slot) else { continue; };
+        item_slot.item = saved_slot.item.and_then(|index| items.get(index).copied());
+        item_slot.unlocked = saved_slot.unlocked;
+        equipped_item = equipped_item.or(item_slot.item);
+    }
+
+    for recipe_entity in recipe_query.iter() {
+        commands.entity(recipe_entity).despawn();
+    }
+
+    for recipe in save.recipes {
+        commands.spawn(recipe);
+    }
+
+    for bench_recipe_entity in bench_recipe_query.iter() {
+        commands.entity(bench_recipe_entity).despawn();
+    }
+
+    commands.spawn_batch(save.bench_recipes);
+
+    for saved_loadout in save.loadouts {
+        commands.spawn(Loadout {
+            name: saved_loadout.name,
+            items: saved_loadout.items.into_iter()
+            .filter_map(|(slot_id, index)| items.get(index).map(|&item| (slot_id, item)))
+            .collect(),
+            action: saved_loadout.action,
+        });
+    }
+
+    commands.spawn_batch(save.runes);
+
+    for &(job_kind, followers) in save.jobs.iter() {
+        if let Some(mut job) = job_query.iter_mut().find(|job| job.kind == job_kind) {
+            job.followers_assigned = followers;
+        }
+    }
+    **followers_assigned = save.jobs.iter().map(|&(_, followers)| followers).sum();
+
+    story.restore(commands.reborrow(), save.story_stage, save.story_count);
+
+    // Player stats are calculated from every item slot no matter which item was equipped.
+    if let Some(item) = equipped_item {
+        commands.trigger(Equipped { item });
+    }
+
+    // Changing the action needs the player stats, so it happens after equipping.
+    if let Some(action) = save.current_action {
+        commands.trigger(ChangeAction::new(action));
+
+        let percent = save.action_progress;
+        commands.queue(move |world: &mut World| {
+            world.resource_mut::<ActionProgress>().percent = percent;
+        });
+    }
+
+    // Offline progress runs last so that it uses the fully restored producers and consumers.
+    let away = unix_time_now().saturating_sub(Duration::from_secs(save.saved_at));
+
+    commands.queue(move |world: &mut World| {
+        apply_offline_progress(world, away);
+    });
+}
+
+fn unix_time_now() -> Duration {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or(Duration::ZERO)
+}
+
+/// Advance the stocks for the time the game was closed and log what changed.
+fn apply_offline_progress(world: &mut World, away: Duration) {
+    if away.is_zero() {
+        return;
+    }
+
+    let before: Vec<f64> = {
+        let stockyard = world.resource::<Stockyard>();
+        StockKind::LIST.iter().map(|&stock_kind| stockyard[stock_kind].current()).collect()
+    };
+
+    fast_forward_stocks(world, away);
+
+    let stockyard = world.resource::<Stockyard>();
+    let mut message = format!("While you were away for {}...", display_duration(away));
+    let mut anything_changed = false;
+
+    for (&stock_kind, before) in StockKind::LIST.iter().zip(before) {
+        let change = stockyard[stock_kind].current() - before;
+
+        // Ignore changes that would display as zero.
+        if change.abs() < 0.005 {
+            continue;
+        }
+
+        anything_changed = true;
+        let _ = write!(message, "\n{:+.2} {}", change, stock_kind);
+    }
+
+    if !anything_changed {
+        message.push_str("\nNothing changed.");
+    }
+
+    world.write_message(LogEntry(message));
+}
+
+fn display_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    let (hours, minutes, seconds) = (seconds / 3600, seconds / 60 % 60, seconds % 60);
+
+    if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
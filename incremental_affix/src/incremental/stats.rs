@@ -1,33 +1,206 @@
-//! ...
+//! Player stats, calculated from every source of stat modifiers.
+//!
+//! Sources are the equipped items and any entity with a [StatSource] component.
+//! The stats are recalculated whenever a source changes.
 
+use std::time::Duration;
+
+use bevy::ecs::system::SystemParam;
+use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
+use itertools::Itertools as _;
 
-use crate::incremental::{action::Action, affinity::Affinity, DotPerSecond, PerSecond};
+use crate::incremental::{action::Action, affinity::Affinity, DotPerSecond, IncrementalStartupSystemSet, PerSecond};
+use crate::incremental::item::{affixive_item::AffixiveItem, equipment::{Equipped, Unequipped}, item_database::ItemDatabase, item_set::set_pieces, item_slot::ItemSlot};
+use crate::stats_builder::StatsBuilder;
 
-/// Stats for all player actions
-#[derive(Debug, Resource, Default)]
-pub struct PlayerActionsStats {
-    pub gather_wood: PlayerActionStats,
-    pub gather_stone: PlayerActionStats,
-    pub hunt: PlayerActionStats,
+pub use crate::stats_builder::{StatContribution, StatOperation};
+
+pub struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<PlayerActionsStats>()
+
+        .add_systems(Startup, spawn_innate_stats.in_set(IncrementalStartupSystemSet))
+        .add_systems(Update, recalculate_on_source_change)
+
+        .add_observer(on_recalculate_stats)
+        .add_observer(on_equip)
+        .add_observer(on_unequip)
+        ;
+    }
 }
 
-impl PlayerActionsStats {
-    pub fn get_bonuses(&self, action: Action) -> Option<&PlayerActionStats> {
+/// A stat that modifiers can affect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum StatId {
+    #[default] // Needed to use in BSN.
+    WoodGain,
+    WoodAffinityChance,
+    WoodAffinityMultiplier,
+    WoodAffinityTime,
+
+    StoneGain,
+    StoneAffinityChance,
+    StoneAffinityMultiplier,
+    StoneAffinityTime,
+
+    HuntGain,
+    HuntAffinityChance,
+    HuntAffinityMultiplier,
+    HuntAffinityTime,
+}
+
+impl StatId {
+    pub const LIST: &[Self] = &[
+        Self::WoodGain,
+        Self::WoodAffinityChance,
+        Self::WoodAffinityMultiplier,
+        Self::WoodAffinityTime,
+
+        Self::StoneGain,
+        Self::StoneAffinityChance,
+        Self::StoneAffinityMultiplier,
+        Self::StoneAffinityTime,
+
+        Self::HuntGain,
+        Self::HuntAffinityChance,
+        Self::HuntAffinityMultiplier,
+        Self::HuntAffinityTime,
+    ];
+
+    /// The gain, affinity chance, affinity multiplier, and affinity time stats of the action.
+    pub fn for_action(action: Action) -> Option<[Self; 4]> {
         match action {
             Action::Explore => None,
-            Action::GatherWood => Some(&self.gather_wood),
-            Action::GatherStone => Some(&self.gather_stone),
-            Action::Hunt => Some(&self.hunt),
+            Action::GatherWood => Some([StatId::WoodGain, StatId::WoodAffinityChance, StatId::WoodAffinityMultiplier, StatId::WoodAffinityTime]),
+            Action::GatherStone => Some([StatId::StoneGain, StatId::StoneAffinityChance, StatId::StoneAffinityMultiplier, StatId::StoneAffinityTime]),
+            Action::Hunt => Some([StatId::HuntGain, StatId::HuntAffinityChance, StatId::HuntAffinityMultiplier, StatId::HuntAffinityTime]),
             Action::RenderCarcass => None,
             Action::CookMeat => None,
             Action::CreateFollowers => None,
         }
     }
+
+    /// Whether the stat is how much of a stock an action gathers.
+    pub fn is_gain(self) -> bool {
+        matches!(self, StatId::WoodGain | StatId::StoneGain | StatId::HuntGain)
+    }
+
+    /// Lowest and highest value the stat can have.
+    pub fn limits(self) -> (Option<f64>, Option<f64>) {
+        match self {
+            // Chances past certain are meaningless.
+            StatId::WoodAffinityChance | StatId::StoneAffinityChance | StatId::HuntAffinityChance => (Some(0.0), Some(1.0)),
+
+            // Negative durations cannot be turned into a Duration.
+            _ => (Some(0.0), None),
+        }
+    }
+}
+
+impl std::fmt::Display for StatId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match *self {
+            StatId::WoodGain => "Wood per second",
+            StatId::WoodAffinityChance => "Wood affinity chance",
+            StatId::WoodAffinityMultiplier => "Wood affinity multiplier",
+            StatId::WoodAffinityTime => "Wood affinity time",
+
+            StatId::StoneGain => "Stone per second",
+            StatId::StoneAffinityChance => "Stone affinity chance",
+            StatId::StoneAffinityMultiplier => "Stone affinity multiplier",
+            StatId::StoneAffinityTime => "Stone affinity time",
+
+            StatId::HuntGain => "Carcasses per second",
+            StatId::HuntAffinityChance => "Hunt affinity chance",
+            StatId::HuntAffinityMultiplier => "Hunt affinity multiplier",
+            StatId::HuntAffinityTime => "Hunt affinity time",
+        })
+    }
+}
+
+/// A change to a single stat.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StatModifier {
+    pub stat: StatId,
+    pub operation: StatOperation,
+    pub value: f64,
+}
+
+/// Entity that contributes modifiers to the player's stats, such as a building, research, or buff.
+///
+/// Equipped items are sources without needing this component.
+/// Stat breakdowns name the source by its [Name].
+#[derive(Debug, Clone, Default, Component)]
+pub struct StatSource {
+    pub modifiers: Vec<StatModifier>,
+}
+
+/// Event to fire to recalculate [PlayerActionsStats] right away.
+///
+/// Changes to sources are picked up without it by the next update.
+#[derive(Debug, Event)]
+pub struct RecalculateStats;
+
+/// Stats for all player actions
+#[derive(Debug, Resource, Default)]
+pub struct PlayerActionsStats {
+    values: HashMap<StatId, f64>,
+
+    /// The contributions that went into each stat.
+    breakdowns: HashMap<StatId, Vec<StatContribution>>,
+}
+
+impl PlayerActionsStats {
+    /// Calculate every stat from the contributions of every source.
+    pub fn calculate(contributions: impl IntoIterator<Item = (StatId, StatContribution)>) -> Self {
+        let mut builders: HashMap<StatId, StatsBuilder> = HashMap::default();
+
+        for (stat, contribution) in contributions {
+            builders.entry(stat).or_default().add_contribution(contribution);
+        }
+
+        for (&stat, builder) in builders.iter_mut() {
+            let (minimum, maximum) = stat.limits();
+            if let Some(minimum) = minimum { builder.set_minimum(minimum); }
+            if let Some(maximum) = maximum { builder.set_maximum(maximum); }
+        }
+
+        let values = builders.iter().map(|(&stat, builder)| (stat, builder.calculate())).collect();
+        let breakdowns = builders.into_iter().map(|(stat, builder)| (stat, builder.into_contributions())).collect();
+
+        Self { values, breakdowns }
+    }
+
+    /// The value of the stat. Stats without any modifiers are zero.
+    pub fn get(&self, stat: StatId) -> f64 {
+        self.values.get(&stat).copied().unwrap_or(0.0)
+    }
+
+    /// Where the value of the stat came from.
+    pub fn breakdown(&self, stat: StatId) -> &[StatContribution] {
+        self.breakdowns.get(&stat).map(Vec::as_slice).unwrap_or_default()
+    }
+
+    pub fn get_bonuses(&self, action: Action) -> Option<PlayerActionStats> {
+        let [gain, affinity_chance, affinity_multiplier, affinity_time] = StatId::for_action(action)?;
+
+        Some(PlayerActionStats {
+            base_gain_per_second: self.get(gain).per_second(),
+            affinity: Affinity {
+                chance: self.get(affinity_chance),
+                multiplier: self.get(affinity_multiplier),
+                time: Duration::from_secs_f64(self.get(affinity_time)),
+            },
+        })
+    }
 }
 
 /// Stats for a specific action
-#[derive(Debug, Default)]
+#[derive(Debug, Clone, Copy, Default)]
 pub struct PlayerActionStats {
     pub base_gain_per_second: PerSecond,
     pub affinity: Affinity,
@@ -37,4 +210,159 @@ impl PlayerActionStats {
     pub fn has_base_gain(&self) -> bool {
         self.base_gain_per_second != 0.per_second()
     }
-}
\ No newline at end of file
+}
+
+/// Stats the player has without any equipment.
+fn spawn_innate_stats(mut commands: Commands) {
+    let affinity = |chance, multiplier, time| [
+        StatModifier { stat: chance, operation: StatOperation::Offset, value: 0.5 },
+        StatModifier { stat: multiplier, operation: StatOperation::Offset, value: 2.0 },
+        StatModifier { stat: time, operation: StatOperation::Offset, value: 1.0 },
+    ];
+
+    let modifiers = [
+        affinity(StatId::WoodAffinityChance, StatId::WoodAffinityMultiplier, StatId::WoodAffinityTime),
+        affinity(StatId::StoneAffinityChance, StatId::StoneAffinityMultiplier, StatId::StoneAffinityTime),
+    ].into_iter().flatten().collect();
+
+    commands.spawn((Name::new("Innate stats"), StatSource { modifiers }));
+}
+
+fn recalculate_on_source_change(
+    mut commands: Commands,
+
+    changed_source_query: Query<(), Changed<StatSource>>,
+    mut removed_sources: RemovedComponents<StatSource>,
+
+    changed_item_slot_query: Query<(), Changed<ItemSlot>>,
+    changed_item_query: Query<Entity, Changed<AffixiveItem>>,
+    item_slot_query: Query<&ItemSlot>,
+
+    item_db: Res<ItemDatabase>,
+) {
+    let removed_source = removed_sources.read().count() > 0;
+    let equipped_item_changed = changed_item_query.iter()
+    .any(|item| item_slot_query.iter().any(|item_slot| item_slot.item == Some(item)));
+
+    // Reloaded item definitions can change set bonuses.
+    let set_bonuses_changed = item_db.is_changed() && !item_db.is_added();
+
+    if removed_source || equipped_item_changed || set_bonuses_changed || !changed_source_query.is_empty() || !changed_item_slot_query.is_empty() {
+        commands.trigger(RecalculateStats);
+    }
+}
+
+fn on_recalculate_stats(
+    _event: On<RecalculateStats>,
+
+    mut player_actions_stats: ResMut<PlayerActionsStats>,
+    stat_sources: StatSources,
+) {
+    *player_actions_stats = stat_sources.calculate();
+}
+
+/// Equipping an item recalculates right away, so actions started afterwards use the new stats.
+fn on_equip(
+    _event: On<Equipped>,
+
+    mut player_actions_stats: ResMut<PlayerActionsStats>,
+    stat_sources: StatSources,
+) {
+    *player_actions_stats = stat_sources.calculate();
+}
+
+fn on_unequip(
+    _event: On<Unequipped>,
+
+    mut player_actions_stats: ResMut<PlayerActionsStats>,
+    stat_sources: StatSources,
+) {
+    *player_actions_stats = stat_sources.calculate();
+}
+
+/// Every source of stat modifiers, for calculating stats.
+#[derive(SystemParam)]
+pub struct StatSources<'w, 's> {
+    source_query: Query<'w, 's, (Entity, &'static StatSource, Option<&'static Name>)>,
+    item_query: Query<'w, 's, &'static AffixiveItem>,
+    item_slot_query: Query<'w, 's, &'static ItemSlot>,
+    item_db: Res<'w, ItemDatabase>,
+}
+
+impl StatSources<'_, '_> {
+    /// Calculate the stats from the sources as they are.
+    pub fn calculate(&self) -> PlayerActionsStats {
+        self.calculate_with_items(self.item_slot_query.iter().filter_map(|item_slot| item_slot.item))
+    }
+
+    /// Calculate the stats as if the item were equipped into the first item slot that accepts it.
+    ///
+    /// Nothing is actually equipped. Returns `None` if no item slot accepts the item.
+    pub fn calculate_equipping(&self, item_entity: Entity) -> Option<PlayerActionsStats> {
+        let item = self.item_query.get(item_entity).ok()?;
+        let slot = self.item_slot_query.iter().find(|item_slot| item_slot.accepts(item))?;
+
+        let equipped_items = self.item_slot_query.iter()
+        .filter(|item_slot| item_slot.id != slot.id)
+        .filter_map(|item_slot| item_slot.item)
+        // The item might be moving from another slot.
+        .filter(|&equipped_item| equipped_item != item_entity)
+        .chain([item_entity]);
+
+        Some(self.calculate_with_items(equipped_items))
+    }
+
+    fn calculate_with_items(&self, equipped_items: impl Iterator<Item = Entity>) -> PlayerActionsStats {
+        fn contribution(source: Entity, name: &str, modifier: StatModifier) -> (StatId, StatContribution) {
+            (modifier.stat, StatContribution {
+                source,
+                name: name.to_string(),
+                operation: modifier.operation,
+                value: modifier.value,
+            })
+        }
+
+        let mut contributions = vec![];
+
+        for (source, stat_source, name) in self.source_query.iter() {
+            let name = name.map_or("Unknown", Name::as_str);
+            contributions.extend(stat_source.modifiers.iter().map(|&modifier| contribution(source, name, modifier)));
+        }
+
+        let equipped_items: Vec<(Entity, &AffixiveItem)> = equipped_items
+        .filter_map(|item_entity| self.item_query.get(item_entity).ok().map(|item| (item_entity, item)))
+        .collect();
+
+        for &(item_entity, item) in equipped_items.iter() {
+            for (name, modifier, value) in item.modifiers() {
+                contributions.extend(modifier.kind.stat_modifiers(value).map(|modifier| contribution(item_entity, &name, modifier)));
+            }
+
+            let wear_penalty = item.wear_penalty();
+            if wear_penalty < 0.0 {
+                let worn_gains = item.modifiers()
+                .flat_map(|(_, modifier, _)| modifier.kind.stat_effects().iter().map(|&(stat, _)| stat))
+                .filter(|stat| stat.is_gain())
+                .unique();
+
+                let name = format!("Worn {}", item.name());
+                contributions.extend(worn_gains.map(|stat| contribution(item_entity, &name, StatModifier { stat, operation: StatOperation::More, value: wear_penalty })));
+            }
+        }
+
+        for (set_id, pieces) in set_pieces(equipped_items.iter().map(|&(_, item)| item)) {
+            let Some(set) = self.item_db.set(set_id) else { continue; };
+
+            // Set bonuses are attributed to the first equipped piece.
+            let &(source, _) = equipped_items.iter().find(|(_, item)| item.set() == Some(set_id))
+            .expect("A set with pieces must have an equipped piece.");
+
+            for bonus in set.active_bonuses(pieces.len()) {
+                let name = set.bonus_name(bonus);
+                contributions.extend(bonus.kind.stat_modifiers(bonus.value).map(|modifier| contribution(source, &name, modifier)));
+            }
+        }
+
+        PlayerActionsStats::calculate(contributions)
+    }
+}
@@ -8,10 +8,11 @@ use std::ops::*;
 
 use bevy::ecs::VariantDefaults;
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::incremental::stock::stockyard::{tick_stockyard_system, Stockyard};
 use crate::incremental::{IncrementalPlugin, IncrementalStartupSystemSet, PerSecond};
-use crate::incremental::stock::producer_consumer::{consume_modifiers, init_follower_stockyard_producer_consumer, produce_modifiers, update_follower_modifier, StockSystems};
+use crate::incremental::stock::producer_consumer::{consume_modifiers, init_follower_stockyard_producer_consumer, produce_modifiers, run_stock_tick, update_follower_modifier, StockSystems, StockTick, StockTickMultiplier};
 
 pub mod producer_consumer;
 pub mod stockyard;
@@ -23,17 +24,25 @@ impl Plugin for StockPlugin {
     fn build(&self, app: &mut App) {
         app
         .init_resource::<Stockyard>()
+        .init_resource::<StockTickMultiplier>()
+        .init_schedule(StockTick)
+        .configure_sets(StockTick, (
+            StockSystems::PreConsume,
+            StockSystems::Consume,
+            StockSystems::PostConsume,
+            StockSystems::Produce,
+        ).chain())
         .add_systems(Startup, init_follower_stockyard_producer_consumer.in_set(IncrementalStartupSystemSet))
-        .add_systems(FixedUpdate, (tick_stockyard_system, on_total::on_stock_total_firer))
-        .add_systems(FixedUpdate, consume_modifiers.in_set(StockSystems::Consume))
-        .add_systems(FixedUpdate, update_follower_modifier.in_set(StockSystems::PostConsume).after(StockSystems::Consume))
-        .add_systems(FixedUpdate, produce_modifiers.in_set(StockSystems::Produce).after(StockSystems::PostConsume))
+        .add_systems(FixedUpdate, (tick_stockyard_system, on_total::on_stock_total_firer, run_stock_tick))
+        .add_systems(StockTick, consume_modifiers.in_set(StockSystems::Consume))
+        .add_systems(StockTick, update_follower_modifier.in_set(StockSystems::PostConsume))
+        .add_systems(StockTick, produce_modifiers.in_set(StockSystems::Produce))
         ;
     }
 }
 
 /// A numeric resource controlled by the player.
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Component, VariantDefaults)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Component, VariantDefaults, Serialize, Deserialize)]
 pub enum StockKind {
     // #[TODO(Havvy)]: Move this out of Resources.
     // It's currently here to show up in the resources sidebar.
@@ -180,6 +189,29 @@ impl PartialOrd<u32> for Stock {
     }
 }
 
+/// Saving and restoring
+impl Stock {
+    pub fn current(&self) -> f64 {
+        self.current
+    }
+
+    pub fn maximum(&self) -> Option<f64> {
+        self.maximum
+    }
+
+    pub fn total_produced(&self) -> f64 {
+        self.total_produced
+    }
+
+    /// Overwrite the stock with values from a saved game.
+    pub fn restore(&mut self, current: f64, maximum: Option<f64>, total_produced: f64) {
+        self.current = current;
+        self.maximum = maximum;
+        self.total_produced = total_produced;
+        self.has_changed = true;
+    }
+}
+
 /// Reading stock values to strings.
 impl Stock {
     #[allow(unused)]
@@ -1,10 +1,19 @@
+use std::time::Duration;
+
+use bevy::ecs::schedule::ScheduleLabel;
 use bevy::prelude::*;
 use bevy::platform::collections::HashMap;
 
 use crate::incremental::stock::{StockKind, Stockyard, StockPerSecond};
 use crate::incremental::{DotPerSecond as _, PerSecond};
 
-// #[TODO(Havvy)]: Make sure these systems happen in this order.
+/// Schedule that moves stocks around through the [StockyardProducerConsumer]s.
+/// 
+/// Runs once per `FixedUpdate`, and many times over when fast-forwarding.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, ScheduleLabel)]
+pub struct StockTick;
+
+/// Systems in the [StockTick] schedule. Run in the order declared.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
 pub enum StockSystems {
     PreConsume,
@@ -13,6 +22,39 @@ pub enum StockSystems {
     Produce,
 }
 
+/// How many ticks a single run of [StockTick] represents.
+/// 
+/// This is `1.0` except while fast-forwarding.
+#[derive(Debug, Resource, Deref, DerefMut)]
+pub struct StockTickMultiplier(pub f64);
+
+impl Default for StockTickMultiplier {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+pub fn run_stock_tick(world: &mut World) {
+    world.run_schedule(StockTick);
+}
+
+/// Run the [StockTick] schedule for `duration` worth of fixed updates.
+/// 
+/// A second worth of ticks is batched into each run so that long durations stay fast.
+pub fn fast_forward_stocks(world: &mut World, duration: Duration) {
+    let ticks_per_second = 1.0 / world.resource::<Time<Fixed>>().timestep().as_secs_f64();
+    let mut ticks = duration.as_secs_f64() * ticks_per_second;
+
+    while ticks > 0.0 {
+        let batch = f64::min(ticks, ticks_per_second);
+        **world.resource_mut::<StockTickMultiplier>() = batch;
+        world.run_schedule(StockTick);
+        ticks -= batch;
+    }
+
+    **world.resource_mut::<StockTickMultiplier>() = 1.0;
+}
+
 /// An effect that consumes or produces stocks over time.
 #[derive(Debug, Component)]
 pub struct StockyardProducerConsumer {
@@ -64,6 +106,7 @@ impl StockyardProducerConsumer {
 // A afterwards will have 5.
 pub fn consume_modifiers(
     mut stockyard: ResMut<Stockyard>,
+    tick_multiplier: Res<StockTickMultiplier>,
     mut consumption_table: Local<HashMap<StockKind, f64>>,
 
     mut modifier_query: Query<&mut StockyardProducerConsumer>,
@@ -76,7 +119,7 @@ pub fn consume_modifiers(
     // Sum into the consumption table the total consumption per stock kind.
     for consumption in modifier_query.iter().flat_map(|m| &m.consumes).copied() {
         let entry = consumption_table.entry(consumption.kind).or_insert(0.0);
-        *entry += consumption.per_second.per_tick();
+        *entry += consumption.per_second.per_tick() * **tick_multiplier;
     }
 
     // Remove the total consumption from the stockyard, replacing it with the percentage actually consumed.
@@ -93,18 +136,19 @@ pub fn consume_modifiers(
 
         // Actually consume what can be consumed.
         for StockPerSecond { kind: stock_kind, per_second: consumption} in modifier.consumes.iter().copied() {
-            stockyard[stock_kind] -= consumption.per_tick() * modifier.consumption_fullfilled;
+            stockyard[stock_kind] -= consumption.per_tick() * **tick_multiplier * modifier.consumption_fullfilled;
         }
     }
 }
 
 pub fn produce_modifiers (
     mut stockyard: ResMut<Stockyard>,
+    tick_multiplier: Res<StockTickMultiplier>,
 
     pc_query: Query<&mut StockyardProducerConsumer>,
 ) {
     for production in pc_query.iter().flat_map(|pc| pc.produces.iter()) {
-        stockyard[production.kind] += production.per_second.per_tick();
+        stockyard[production.kind] += production.per_second.per_tick() * **tick_multiplier;
     }
 }
 
@@ -1,8 +1,9 @@
 //! Observers related to the story.
 
-use bevy::{ecs::observer::IntoObserver, prelude::*};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 
-use crate::incremental::{action::{Action, Explore, LearnAction, ResetPlayerAction}, item::{affixive_item::AffixiveItem, base::Base, craft::{Crafted, Recipe}}, log::LogEntry, stock::{StockKind, on_total::OnStockTotalProduced, stockyard::Stockyard}};
+use crate::incremental::{action::{Action, Explore, LearnAction, ResetPlayerAction}, item::{ItemObtained, affixive_item::{AffixiveItem, ItemLevel}, base::Base, craft::{BenchRecipe, Crafted, Recipe}, definitions::{AffixKind, ArtifactId}, item_database::ItemDatabase, item_slot::{ItemSlotId, UnlockItemSlot}, modifier::AffixId}, log::LogEntry, stock::{StockKind, on_total::OnStockTotalProduced, stockyard::Stockyard}};
 
 pub struct StoryPlugin;
 
@@ -14,29 +15,144 @@ impl Plugin for StoryPlugin {
     }
 }
 
+/// How far the player has progressed through the story.
+/// 
+/// Each stage other than `Finished` has an observer waiting for the next story beat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StoryStage {
+    FirstExplore,
+    CraftMakeshiftTools,
+    CraftStoneTools,
+    SecondExplore,
+    Finished,
+}
+
+impl StoryStage {
+    fn add_observer(self, commands: &mut Commands) -> Option<Entity> {
+        let observer = match self {
+            StoryStage::FirstExplore => commands.add_observer(on_first_explore),
+            StoryStage::CraftMakeshiftTools => commands.add_observer(on_craft_makeshift_tools),
+            StoryStage::CraftStoneTools => commands.add_observer(on_craft_stone_tools),
+            StoryStage::SecondExplore => commands.add_observer(on_second_explore),
+            StoryStage::Finished => return None,
+        };
+
+        Some(observer.id())
+    }
+}
+
 #[derive(Debug, Component)]
-struct StoryObservers {
-    observer: Entity,
-    count: u32,
+pub(crate) struct StoryObservers {
+    pub(crate) stage: StoryStage,
+    observer: Option<Entity>,
+    pub(crate) count: u32,
 }
 
 impl StoryObservers {
-    fn replace<M>(&mut self, mut commands: Commands, observer: impl IntoObserver<M>) {
-        self.clear(commands.reborrow());
-        let entity = commands.add_observer(observer).id();
-        self.observer = entity;
-    }
+    fn replace(&mut self, mut commands: Commands, stage: StoryStage) {
+        if let Some(observer) = self.observer.take() {
+            commands.entity(observer).despawn();
+        }
 
-    fn clear(&mut self, mut commands: Commands) {
-        commands.entity(self.observer).despawn();
+        self.stage = stage;
+        self.observer = stage.add_observer(&mut commands);
         self.count = 0;
     }
+
+    fn clear(&mut self, commands: Commands) {
+        self.replace(commands, StoryStage::Finished);
+    }
+
+    /// Move the story to the stage of a saved game.
+    pub(crate) fn restore(&mut self, commands: Commands, stage: StoryStage, count: u32) {
+        self.replace(commands, stage);
+        self.count = count;
+    }
+}
+
+/// Artifact given when the player remembers they are a deity.
+pub(crate) const REMEMBERED_ARTIFACT: &str = "hands_of_the_maker";
+
+/// Bench recipes learned by crafting stone tools.
+fn stone_tools_bench_recipes() -> [BenchRecipe; 2] {
+    [
+        BenchRecipe {
+            affix_kind: AffixKind::Prefix,
+            affix: AffixId("wood_base_gain".into()),
+            tier: 1,
+            resources: smallvec::smallvec![(StockKind::Wood, 25.0), (StockKind::Stone, 10.0)],
+        },
+        BenchRecipe {
+            affix_kind: AffixKind::Suffix,
+            affix: AffixId("tool_affinity_chance".into()),
+            tier: 1,
+            resources: smallvec::smallvec![(StockKind::Stone, 25.0)],
+        },
+    ]
+}
+
+/// Bench recipes learned when the player thinks to make a hunting bow.
+fn hunting_bench_recipes() -> [BenchRecipe; 1] {
+    [
+        BenchRecipe {
+            affix_kind: AffixKind::Prefix,
+            affix: AffixId("hunt_base_gain".into()),
+            tier: 1,
+            resources: smallvec::smallvec![(StockKind::Wood, 20.0), (StockKind::Bone, 5.0)],
+        },
+    ]
+}
+
+/// Spawn the bench recipes, leaving out any whose affix is not in the item definitions.
+fn spawn_bench_recipes(mut commands: Commands, item_db: &ItemDatabase, bench_recipes: impl IntoIterator<Item = BenchRecipe>) {
+    for bench_recipe in bench_recipes {
+        if !bench_recipe.is_defined(item_db) {
+            warn!("Bench recipe for undefined tier {} of {} `{}`.", bench_recipe.tier, bench_recipe.affix_kind, bench_recipe.affix);
+            continue;
+        }
+
+        commands.spawn(bench_recipe);
+    }
+}
+
+/// Item slots unlocked when the player thinks to make a hunting bow.
+const HUNTING_ITEM_SLOTS: &[&str] = &["garment"];
+
+/// Item slots unlocked when the player remembers they are a deity.
+const REMEMBERED_ITEM_SLOTS: &[&str] = &["totem", "altar_relic"];
+
+/// Recipes for the items of the [HUNTING_ITEM_SLOTS].
+fn hunting_slot_recipes() -> [Recipe; 1] {
+    [
+        Recipe {
+            base: Base::HideWrap,
+            resources: smallvec::smallvec![(StockKind::Carcass, 3.0)],
+            item_level: ItemLevel(5),
+        },
+    ]
+}
+
+/// Recipes for the items of the [REMEMBERED_ITEM_SLOTS].
+fn remembered_slot_recipes() -> [Recipe; 2] {
+    [
+        Recipe {
+            base: Base::BoneTotem,
+            resources: smallvec::smallvec![(StockKind::Bone, 15.0), (StockKind::Wood, 10.0)],
+            item_level: ItemLevel(10),
+        },
+        Recipe {
+            base: Base::StoneIdol,
+            resources: smallvec::smallvec![(StockKind::Stone, 40.0), (StockKind::Diamond, 1.0)],
+            item_level: ItemLevel(10),
+        },
+    ]
 }
 
 fn setup(mut commands: Commands) {
-    let on_first_explore = commands.add_observer(on_first_explore).id();
+    let observer = StoryStage::FirstExplore.add_observer(&mut commands);
     commands.spawn(StoryObservers {
-        observer: on_first_explore,
+        stage: StoryStage::FirstExplore,
+        observer,
         count: 0,
     });
 
@@ -56,7 +172,7 @@ fn on_first_explore(
     mut stockyard: ResMut<Stockyard>,
     mut log_event_writer: MessageWriter<LogEntry>,
 ) {
-    observers.replace(commands.reborrow(), on_craft_makeshift_tools);
+    observers.replace(commands.reborrow(), StoryStage::CraftMakeshiftTools);
 
     stockyard[StockKind::BranchesAndPebbles] += 1.0;
     log_event_writer.write(LogEntry::from([
@@ -88,7 +204,7 @@ fn on_craft_makeshift_tools(
         return;
     }
 
-    observers.replace(commands.reborrow(), on_craft_stone_tools);
+    observers.replace(commands.reborrow(), StoryStage::CraftStoneTools);
     
     log_event_writer.write(LogEntry::from([
         "You sit down and cobble together some makeshift logging and mining tools using the sticks and pebbles laying around.",
@@ -101,6 +217,7 @@ fn on_craft_makeshift_tools(
         Recipe {
             base: Base::StoneTools,
             resources: smallvec::smallvec![(StockKind::Wood, 5.0), (StockKind::Stone, 5.0)],
+            item_level: ItemLevel(10),
         }
     });
 }
@@ -110,6 +227,7 @@ fn on_craft_stone_tools(
     mut commands: Commands,
     mut observers: Single<&mut StoryObservers>,
 
+    item_db: Res<ItemDatabase>,
     item_query: Query<&AffixiveItem>,
 
     mut log_event_writer: MessageWriter<LogEntry>,
@@ -120,13 +238,16 @@ fn on_craft_stone_tools(
         return;
     }
 
-    observers.replace(commands.reborrow(), on_second_explore);
+    observers.replace(commands.reborrow(), StoryStage::SecondExplore);
 
     log_event_writer.write(LogEntry::from([
         "After sitting down and crafting the stone tools, you again note the ease at which you made these.",
         "Perhaps you are a mighty crafter?",
+        "With a proper workbench, you could even carve specific improvements into your tools.",
         "Perhaps some more walking around will help you regain your memory."
     ]));
+
+    spawn_bench_recipes(commands, &item_db, stone_tools_bench_recipes());
 }
 
 fn on_second_explore(
@@ -134,6 +255,7 @@ fn on_second_explore(
     mut commands: Commands,
     mut observers: Single<&mut StoryObservers>,
 
+    item_db: Res<ItemDatabase>,
     mut log_event_writer: MessageWriter<LogEntry>,
 ) {
     observers.count += 1;
@@ -152,8 +274,16 @@ fn on_second_explore(
                 Recipe {
                     base: Base::WoodenHunt,
                     resources: smallvec::smallvec![(StockKind::Wood, 5.0)],
+                    item_level: ItemLevel(5),
                 }
             });
+
+            spawn_bench_recipes(commands.reborrow(), &item_db, hunting_bench_recipes());
+            commands.spawn_batch(hunting_slot_recipes());
+
+            for &slot in HUNTING_ITEM_SLOTS {
+                commands.trigger(UnlockItemSlot { slot: ItemSlotId(slot.into()) });
+            }
         },
 
         10 => {
@@ -168,6 +298,21 @@ fn on_second_explore(
 
             commands.trigger(LearnAction { action: Action::CreateFollowers });
             commands.trigger(ResetPlayerAction);
+
+            for &slot in REMEMBERED_ITEM_SLOTS {
+                commands.trigger(UnlockItemSlot { slot: ItemSlotId(slot.into()) });
+            }
+
+            commands.spawn_batch(remembered_slot_recipes());
+
+            match item_db.create_artifact(&ArtifactId(REMEMBERED_ARTIFACT.into())) {
+                Some(artifact) => {
+                    log_event_writer.write(LogEntry::new("With your memory, the old craft returns to your hands."));
+                    let item = commands.spawn(artifact).id();
+                    commands.trigger(ItemObtained { item });
+                },
+                None => warn!("Story artifact `{}` is not in the item definitions.", REMEMBERED_ARTIFACT),
+            }
         },
 
         _ => { /* do nothing */}
@@ -180,4 +325,29 @@ fn on_five_stone_mined(
 ) {
     stockyard[StockKind::Diamond] += 1.0;
     log_event_writer.write(LogEntry("While mining stone, you notice a diamond.".to_string()));
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::incremental::item::{definitions::AffixKind, item_database::ItemDatabase, modifier::AffixId};
+
+    use super::{hunting_bench_recipes, stone_tools_bench_recipes};
+
+    #[test]
+    fn story_bench_recipes_are_in_the_item_definitions() {
+        let item_db = ItemDatabase::new();
+        let bench_recipes = stone_tools_bench_recipes().into_iter().chain(hunting_bench_recipes()).collect::<Vec<_>>();
+
+        for bench_recipe in bench_recipes.iter() {
+            assert!(bench_recipe.is_defined(&item_db), "Tier {} of {} `{}` is not defined.", bench_recipe.tier, bench_recipe.affix_kind, bench_recipe.affix);
+        }
+
+        let mut misspelled = bench_recipes[0].clone();
+        misspelled.affix = AffixId("wood_base_gian".into());
+        assert!(!misspelled.is_defined(&item_db));
+
+        let mut wrong_kind = bench_recipes[0].clone();
+        wrong_kind.affix_kind = AffixKind::Suffix;
+        assert!(!wrong_kind.is_defined(&item_db));
+    }
+}
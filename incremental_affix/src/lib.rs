@@ -0,0 +1,13 @@
+//! An incremental game mechanically about building affixive items to progress.
+//! 
+//! The game itself is in `main.rs`. This library also backs the `simulate` binary.
+
+mod incremental;
+mod stats_builder;
+mod ui;
+
+pub mod headless;
+
+pub use incremental::IncrementalPlugin;
+pub use incremental::save::{RestoreSystemSet, SavePlugin};
+pub use ui::{UiPlugin, UiSetupSystemSet, tooltip::TooltipPlugin};
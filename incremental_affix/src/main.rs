@@ -1,22 +1,19 @@
-mod incremental;
-mod stats_builder;
-mod ui;
-
 use bevy::prelude::*;
 
-use crate::{incremental::IncrementalStartupSystemSet, ui::UiSetupSystemSet};
+use incremental_affix::{IncrementalPlugin, RestoreSystemSet, SavePlugin, TooltipPlugin, UiPlugin, UiSetupSystemSet};
 
 fn main() {
     App::new()
     .add_plugins((
         DefaultPlugins,
 
-        incremental::IncrementalPlugin,
+        IncrementalPlugin,
+        SavePlugin,
 
-        ui::tooltip::TooltipPlugin,
+        TooltipPlugin,
 
-        ui::UiPlugin,
+        UiPlugin,
     ))
-    .configure_sets(Startup, UiSetupSystemSet.after(IncrementalStartupSystemSet))
+    .configure_sets(Startup, UiSetupSystemSet.after(RestoreSystemSet))
     .run();
-}
\ No newline at end of file
+}
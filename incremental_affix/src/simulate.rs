@@ -0,0 +1,22 @@
+//! Runs the game without a window following a scripted plan.
+//! 
+//! Usage: `cargo run --bin simulate -- plans/followers.ron`
+//! 
+//! See `headless` for the plan format.
+
+fn main() {
+    let Some(path) = std::env::args().nth(1) else {
+        eprintln!("Usage: simulate <plan.ron>");
+        std::process::exit(2);
+    };
+
+    let plan = match incremental_affix::headless::Plan::load(&path) {
+        Ok(plan) => plan,
+        Err(error) => {
+            eprintln!("{}", error);
+            std::process::exit(1);
+        }
+    };
+
+    incremental_affix::headless::run(plan);
+}
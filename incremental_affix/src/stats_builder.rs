@@ -1,7 +1,66 @@
+use bevy::prelude::Entity;
+
+/// How a modifier changes a stat.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatOperation {
+    /// The stat's base value. Only the highest base counts.
+    Base,
+
+    /// Added to the base.
+    Offset,
+
+    /// Summed with other increases and multiplies the base and offsets, e.g. `0.2` for +20%.
+    Increased,
+
+    /// Multiplied with other "more" modifiers after increases, e.g. `-0.2` for 20% less.
+    More,
+
+    /// Added after every multiplier.
+    Added,
+}
+
+/// A modifier that went into a stat and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StatContribution {
+    /// Entity of the item or other source of the modifier.
+    pub source: Entity,
+
+    /// Name of the affix or source, e.g. "Lumberjack's".
+    pub name: String,
+
+    pub operation: StatOperation,
+    pub value: f64,
+}
+
+impl std::fmt::Display for StatContribution {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let value = self.value;
+
+        match self.operation {
+            StatOperation::Base => write!(f, "{:.2} base", value),
+            StatOperation::Offset => write!(f, "{:+.2}", value),
+            StatOperation::Increased => write!(f, "{:+.0}%", value * 100.0),
+            StatOperation::More if value >= 0.0 => write!(f, "{:.0}% more", value * 100.0),
+            StatOperation::More => write!(f, "{:.0}% less", -value * 100.0),
+            StatOperation::Added => write!(f, "{:+.2} after multipliers", value),
+        }?;
+
+        write!(f, " from \"{}\"", self.name)
+    }
+}
+
+/// Combines modifiers into the value of a single stat.
+///
+/// The value is `((base + offset) * increased * more + added)`, clamped to the limits.
 pub struct StatsBuilder {
     base: f64,
     offset: f64,
     multiplier: f64,
+    more: f64,
+    added: f64,
+    minimum: Option<f64>,
+    maximum: Option<f64>,
+    contributions: Vec<StatContribution>,
 }
 
 impl Default for StatsBuilder {
@@ -10,6 +69,11 @@ impl Default for StatsBuilder {
             base: 0.0,
             offset: 0.0,
             multiplier: 1.0,
+            more: 1.0,
+            added: 0.0,
+            minimum: None,
+            maximum: None,
+            contributions: vec![],
         }
     }
 }
@@ -28,7 +92,9 @@ impl StatsBuilder {
     /// Multipliers are summed, not multiplied.
     /// Just like in Path of Exile.
     /// 
-    /// ```rust
+    /// The module is private, so this is checked by the tests below instead of as a doctest.
+    /// 
+    /// ```ignore
     /// let mut stats_builder = StatsBuilder::default();
     /// stats_builder.set_base(1.0);
     /// stats_builder.add_multiplier(0.2);
@@ -44,11 +110,126 @@ impl StatsBuilder {
         self.add_multiplier(multiplier as f64 / 100.0);
     }
 
+    /// Adds a "more" multiplier, or a "less" one when negative.
+    ///
+    /// Unlike [add_multiplier](Self::add_multiplier), these are multiplied together.
+    /// Two 20% more multipliers are 44% more.
+    pub fn add_more(&mut self, more: f64) {
+        self.more *= 1.0 + more;
+    }
+
+    /// Adds a flat amount after every multiplier.
+    pub fn add_added(&mut self, added: f64) {
+        self.added += added;
+    }
+
+    /// Keeps the stat from going below the minimum. The highest minimum wins.
+    pub fn set_minimum(&mut self, minimum: f64) {
+        self.minimum = Some(self.minimum.map_or(minimum, |current| f64::max(current, minimum)));
+    }
+
+    /// Keeps the stat from going above the maximum. The lowest maximum wins.
+    pub fn set_maximum(&mut self, maximum: f64) {
+        self.maximum = Some(self.maximum.map_or(maximum, |current| f64::min(current, maximum)));
+    }
+
+    /// Applies the contribution's modifier and records it for explaining the stat.
+    pub fn add_contribution(&mut self, contribution: StatContribution) {
+        let value = contribution.value;
+
+        match contribution.operation {
+            StatOperation::Base => self.set_base(value),
+            StatOperation::Offset => self.add_offset(value),
+            StatOperation::Increased => self.add_multiplier(value),
+            StatOperation::More => self.add_more(value),
+            StatOperation::Added => self.add_added(value),
+        }
+
+        self.contributions.push(contribution);
+    }
+
+    /// Every contribution added, in the order they were added.
+    pub fn into_contributions(self) -> Vec<StatContribution> {
+        self.contributions
+    }
+
+    /// Stats without a base are zero before the limits, no matter the other modifiers.
     pub fn calculate(&self) -> f64 {
-        if self.base == 0.0 {
+        let value = if self.base == 0.0 {
             0.0
         } else {
-            (self.base + self.offset) * self.multiplier
-        }
+            (self.base + self.offset) * self.multiplier * self.more + self.added
+        };
+
+        let value = self.minimum.map_or(value, |minimum| f64::max(value, minimum));
+        self.maximum.map_or(value, |maximum| f64::min(value, maximum))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StatsBuilder;
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!((actual - expected).abs() < 1e-9, "expected {}, got {}", expected, actual);
+    }
+
+    #[test]
+    fn increased_multipliers_are_summed_and_more_multipliers_are_multiplied() {
+        let mut stats_builder = StatsBuilder::default();
+        stats_builder.set_base(1.0);
+        stats_builder.add_multiplier(0.2);
+        stats_builder.add_multiplier(0.2);
+        assert_close(stats_builder.calculate(), 1.4);
+
+        stats_builder.add_more(0.2);
+        stats_builder.add_more(0.2);
+        assert_close(stats_builder.calculate(), 1.4 * 1.44);
+
+        stats_builder.add_more(-0.5);
+        assert_close(stats_builder.calculate(), 1.4 * 1.44 * 0.5);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn combination_order() {
+        let mut stats_builder = StatsBuilder::default();
+        stats_builder.set_base(2.0);
+        stats_builder.set_base(1.0);
+        stats_builder.add_offset(1.0);
+        stats_builder.add_multiplier_percent(50);
+        stats_builder.add_more(1.0);
+        stats_builder.add_added(0.5);
+
+        // ((2 + 1) * 1.5 * 2) + 0.5
+        assert_close(stats_builder.calculate(), 9.5);
+    }
+
+    #[test]
+    fn limits_apply_last() {
+        let mut stats_builder = StatsBuilder::default();
+        stats_builder.set_base(0.5);
+        stats_builder.add_multiplier(2.0);
+        stats_builder.add_added(0.5);
+        stats_builder.set_maximum(2.0);
+        stats_builder.set_maximum(1.0);
+        assert_close(stats_builder.calculate(), 1.0);
+
+        let mut stats_builder = StatsBuilder::default();
+        stats_builder.set_base(1.0);
+        stats_builder.add_more(-1.5);
+        stats_builder.set_minimum(-1.0);
+        stats_builder.set_minimum(0.0);
+        assert_close(stats_builder.calculate(), 0.0);
+    }
+
+    #[test]
+    fn stats_without_base_are_zero() {
+        let mut stats_builder = StatsBuilder::default();
+        stats_builder.add_offset(1.0);
+        stats_builder.add_added(1.0);
+        assert_close(stats_builder.calculate(), 0.0);
+
+        stats_builder.set_minimum(0.25);
+        assert_close(stats_builder.calculate(), 0.25);
+    }
+}
@@ -1,12 +1,64 @@
 use bevy::prelude::*;
 
-use crate::incremental::item::affixive_item::AffixiveItem;
+use crate::incremental::item::{affixive_item::{AffixiveItem, Rarity}, item_set::SetDetails, modifier::Affix};
+
+/// Color of equipped set pieces and active set bonuses.
+const SET_ACTIVE_COLOR: Color = Color::srgb(0.3, 0.9, 0.3);
+
+/// Color of missing set pieces and inactive set bonuses.
+const SET_INACTIVE_COLOR: Color = Color::srgb(0.7, 0.7, 0.7);
+
+/// Color of the name and border of normal items in tooltips, where black is hard to read on the background.
+const NORMAL_TOOLTIP_COLOR: Color = Color::srgb(0.85, 0.85, 0.85);
+
+/// Color of the name and border of items of the rarity.
+pub fn rarity_color(rarity: Rarity) -> Color {
+    match rarity {
+        Rarity::Normal => Color::BLACK,
+        Rarity::Magic => Color::srgb(0.2, 0.35, 0.9),
+        Rarity::Rare => Color::srgb(0.85, 0.75, 0.1),
+        Rarity::Artifact => Color::srgb(0.8, 0.35, 0.0),
+    }
+}
+
+/// Text showing the name of the item entity, kept up to date as its affixes change.
+///
+/// The text and its border are colored by the item's rarity.
+#[derive(Debug, Clone, Component, FromTemplate)]
+pub struct ItemNameText(pub Entity);
+
+pub(super) fn update_item_names(
+    changed_item_query: Query<(), Changed<AffixiveItem>>,
+    item_query: Query<&AffixiveItem>,
+    mut item_name_text_query: Query<(Ref<ItemNameText>, &mut Text, &mut TextColor, &mut BorderColor)>,
+) {
+    for (item_name_text, mut text, mut text_color, mut border_color) in item_name_text_query.iter_mut() {
+        if !item_name_text.is_added() && !changed_item_query.contains(item_name_text.0) {
+            continue;
+        }
+
+        let Ok(item) = item_query.get(item_name_text.0) else { continue; };
+        let color = rarity_color(item.rarity());
+
+        text.0 = item.name().into_owned();
+        text_color.0 = color;
+        *border_color = BorderColor::all(color);
+    }
+}
+
+fn display_tier(affix: &Affix) -> String {
+    affix.tier.map(|tier| tier.to_string()).unwrap_or_default()
+}
 
 pub fn spawn_item_details(
     mut commands: Commands,
 
     item: &AffixiveItem,
+    set_details: Option<SetDetails>,
 ) -> Entity {
+    let rarity = item.rarity();
+    let name_color = if rarity == Rarity::Normal { NORMAL_TOOLTIP_COLOR } else { rarity_color(rarity) };
+
     let item_box = commands.spawn((
         Node {
             flex_direction: FlexDirection::Column,
@@ -18,7 +70,7 @@ pub fn spawn_item_details(
             ..default()
         },
         BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
-        BorderColor::all(Color::BLACK),
+        BorderColor::all(name_color),
     )).id();
 
     commands.spawn((
@@ -32,12 +84,65 @@ pub fn spawn_item_details(
 
         children![(
             Text::new(item.name().to_string()),
-            TextFont { font_size: FontSize::Px(16.0), ..default() }
+            TextFont { font_size: FontSize::Px(16.0), ..default() },
+            TextColor(name_color),
         )],
 
         ChildOf(item_box),
     ));
 
+    if rarity == Rarity::Artifact {
+        commands.spawn((
+            Node {
+                justify_content: JustifyContent::Center,
+
+                ..default()
+            },
+
+            children![(
+                Text::new("Artifact - cannot be modified"),
+                TextFont { font_size: FontSize::Px(12.0), ..default() },
+                TextColor(name_color),
+            )],
+
+            ChildOf(item_box),
+        ));
+    } else {
+        commands.spawn((
+            Node {
+                ..default()
+            },
+
+            children![(
+                Text::new(format!("{} - Item Level {}", rarity, item.item_level())),
+                TextFont { font_size: FontSize::Px(12.0), ..default() }
+            )],
+
+            ChildOf(item_box),
+        ));
+    }
+
+    if let Some(durability) = item.durability() {
+        let durability_text = if item.is_broken() {
+            format!("Durability {} - Broken", durability)
+        } else {
+            format!("Durability {}", durability)
+        };
+
+        commands.spawn((
+            Node {
+                ..default()
+            },
+
+            children![(
+                Text::new(durability_text),
+                TextFont { font_size: FontSize::Px(12.0), ..default() }
+            )],
+
+            ChildOf(item_box),
+        ));
+    }
+
     for implicit in item.implicits() {
         commands.spawn((
             Node {
@@ -72,7 +177,7 @@ pub fn spawn_item_details(
                 },
 
                 children![(
-                    Text::new(format!("P {}", prefix.display())),
+                    Text::new(format!("P{} {}", display_tier(prefix), prefix.display())),
                     TextFont { font_size: FontSize::Px(14.0), ..default() }
                 )],
 
@@ -100,7 +205,7 @@ pub fn spawn_item_details(
                 },
 
                 children![(
-                    Text::new(format!("S {}", suffix.display())),
+                    Text::new(format!("S{} {}", display_tier(suffix), suffix.display())),
                     TextFont { font_size: FontSize::Px(14.0), ..default() }
                 )],
 
@@ -109,6 +214,68 @@ pub fn spawn_item_details(
         }
     }
 
+    if item.sockets() > 0 {
+        let sockets_node = commands.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                border: px(1).all(),
+                ..default()
+            },
+
+            children![(
+                Text::new(format!("Sockets {}/{}", item.runes().len(), item.sockets())),
+                TextFont { font_size: FontSize::Px(12.0), ..default() }
+            )],
+
+            ChildOf(item_box),
+        )).id();
+
+        for rune in item.runes() {
+            commands.spawn((
+                Node {
+                    ..default()
+                },
+
+                children![(
+                    Text::new(format!("R {}", rune.display())),
+                    TextFont { font_size: FontSize::Px(14.0), ..default() }
+                )],
+
+                ChildOf(sockets_node),
+            ));
+        }
+    }
+
+    if let Some(set_details) = set_details {
+        let set_node = commands.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                border: px(1).all(),
+                ..default()
+            },
+
+            children![(
+                Text::new(format!("Set: {}", set_details.name)),
+                TextFont { font_size: FontSize::Px(14.0), ..default() }
+            )],
+
+            ChildOf(item_box),
+        )).id();
+
+        let pieces = set_details.pieces.into_iter().map(|(piece, equipped)| (format!("- {}", piece), equipped));
+        let bonuses = set_details.bonuses.into_iter();
+
+        for (text, active) in pieces.chain(bonuses) {
+            commands.spawn((
+                Text::new(text),
+                TextFont { font_size: FontSize::Px(12.0), ..default() },
+                TextColor(if active { SET_ACTIVE_COLOR } else { SET_INACTIVE_COLOR }),
+
+                ChildOf(set_node),
+            ));
+        }
+    }
+
     for tag in item.tags.iter() {
         commands.spawn((
             Node {
@@ -3,10 +3,13 @@ pub mod log;
 mod stocks;
 pub mod tooltip;
 pub mod item;
+pub mod stats;
 
 use bevy::prelude::*;
 
 use crate::incremental::action::KnownActions;
+use crate::incremental::item::{affixive_item::AffixiveItem, item_slot::ItemSlot};
+use crate::incremental::job::Job;
 use crate::ui::stocks::stockyard_ui;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, SystemSet)]
@@ -21,6 +24,7 @@ impl Plugin for UiPlugin {
         .add_systems(Startup, setup.in_set(UiSetupSystemSet))
         .add_systems(Update, (
             stocks::update_stockyard_sidebar,
+            item::update_item_names,
         ))
 
         .add_plugins((
@@ -28,9 +32,14 @@ impl Plugin for UiPlugin {
             screen::action::ActionScreenPlugin,
             screen::population::PopulationScreenPlugin,
             screen::craft::CraftScreenPlugin,
+            screen::stats::StatsScreenPlugin,
+            screen::inventory::InventoryScreenPlugin,
         ))
 
         .add_observer(screen::inventory::on_item_craft)
+        .add_observer(screen::inventory::on_item_obtained)
+        .add_observer(screen::inventory::on_item_unequipped)
+        .add_observer(screen::inventory::on_item_equipped)
         ;
     }
 }
@@ -38,9 +47,23 @@ impl Plugin for UiPlugin {
 fn setup(
     mut commands: Commands,
     known_actions: Res<KnownActions>,
+
+    item_query: Query<(Entity, &AffixiveItem)>,
+    item_slot_query: Query<&ItemSlot>,
+    job_query: Query<&Job>,
 ) {
     commands.spawn(Camera2d);
 
+    // Items and follower assignments can already exist when a saved game was restored.
+    let unequipped_items = item_query.iter()
+    .filter(|&(entity, _)| item_slot_query.iter().all(|item_slot| item_slot.item != Some(entity)))
+    .map(|(entity, item)| (entity, item.name().to_string()))
+    .collect::<Vec<_>>();
+
+    let followers_assigned = job_query.iter()
+    .map(|job| (job.kind, job.followers_assigned))
+    .collect::<Vec<_>>();
+
     commands.queue_spawn_scene(bsn! {
         #Root
         Node {
@@ -67,7 +90,7 @@ fn setup(
                 border: { px(2).bottom() }
             }
             Children [
-                { screen::screens_ui(known_actions) },
+                { screen::screens_ui(known_actions, unequipped_items, followers_assigned) },
                 log::log_ui()
             ]
         ]
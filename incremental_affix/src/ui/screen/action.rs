@@ -7,8 +7,10 @@ use bevy::prelude::*;
 use bevy::ui_widgets::Button;
 
 use crate::incremental::action::{Action, ActionAffinity, ActionProgress, ChangeAction, CurrentAction, KnownActions, LearnAction, NO_CURRENT_ACTION_DISPLAY};
-use crate::incremental::stats::PlayerActionsStats;
+use crate::incremental::stats::{PlayerActionsStats, StatId};
 use crate::ui::screen::{Screen, screen_title};
+use crate::ui::stats::spawn_stat_breakdown;
+use crate::ui::tooltip::{HideTooltip, ShowTooltip};
 
 const BUTTON_ENABLED_COLOR: Color = Color::BLACK;
 const BUTTON_DISABLED_COLOR: Color = Color::Srgba(GRAY);
@@ -139,6 +141,8 @@ fn action_button((action, action_is_known): (Action, bool)) -> impl Scene {
 
         Button
         on(on_press_button_action)
+        on(on_action_hover)
+        on(on_action_out)
 
         Children [
             Text::new(action.to_string())
@@ -195,6 +199,29 @@ fn on_press_button_action(
     commands.trigger(ChangeAction::new(*new_action));    
 }
 
+/// Shows where the stats of the action came from.
+fn on_action_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    player_actions_stats: Res<PlayerActionsStats>,
+
+    actions_query: Query<&Action>,
+) {
+    let action = actions_query.get(event.entity).expect("Action button must have an Action component.");
+    let Some(stat_ids) = StatId::for_action(*action) else { return; };
+
+    let tooltip_content = spawn_stat_breakdown(commands.reborrow(), &player_actions_stats, &stat_ids);
+    commands.trigger(ShowTooltip { content: tooltip_content });
+}
+
+fn on_action_out(
+    _event: On<Pointer<Out>>,
+    mut commands: Commands,
+) {
+    commands.trigger(HideTooltip);
+}
+
 fn on_learn_action(
     event: On<LearnAction>,
 
@@ -1,14 +1,22 @@
 //! The crafting screen.
+//!
+//! The top section crafts new items and runes from recipes. The bench section below it adds
+//! specific affixes to the chosen item.
 
 use bevy::picking::hover::Hovered;
 use bevy::prelude::*;
 use bevy::ui_widgets::{Button, Activate};
 use itertools::Itertools;
 
-use crate::incremental::item::craft::{Recipe, CraftRequest};
+use crate::incremental::item::affixive_item::AffixiveItem;
+use crate::incremental::item::craft::{BenchCraftRequest, BenchRecipe, Recipe, CraftRequest};
 use crate::incremental::item::item_database::ItemDatabase;
+use crate::incremental::item::item_set::EquippedSets;
+use crate::incremental::item::rune::{CraftRuneRequest, RuneRecipe};
+use crate::incremental::log::LogEntry;
+use crate::incremental::stock::StockKind;
 use crate::ui::screen::screen_title;
-use crate::ui::{item::spawn_item_details, tooltip};
+use crate::ui::{item::{ItemNameText, spawn_item_details}, tooltip};
 use super::Screen;
 
 pub struct CraftScreenPlugin;
@@ -16,7 +24,7 @@ pub struct CraftScreenPlugin;
 impl Plugin for CraftScreenPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, on_new_recipe)
+        .add_systems(Update, (on_new_recipe, on_new_rune_recipe, on_new_bench_item, on_new_bench_recipe))
         ;
     }
 }
@@ -33,6 +41,44 @@ pub struct CraftButtonOf(pub Entity);
 #[relationship_target(relationship = CraftButtonOf)]
 pub struct CorrespondingCraftButton(Entity);
 
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingRuneCraftButton)]
+pub struct RuneCraftButtonOf(pub Entity);
+
+/// Despawns the button when the rune recipe is replaced.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = RuneCraftButtonOf, linked_spawn)]
+pub struct CorrespondingRuneCraftButton(Entity);
+
+/// Marker component for the [Node] that contains the buttons for choosing the bench's item
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct BenchItemList;
+
+/// Marker component for the [Node] that contains the bench recipe buttons
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct BenchRecipeList;
+
+/// Marker component for the bench item button of the item that bench recipes are used on
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct BenchTarget;
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingBenchItemButton)]
+pub struct BenchItemButtonOf(pub Entity);
+
+/// Despawns the button when the item is despawned.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = BenchItemButtonOf, linked_spawn)]
+pub struct CorrespondingBenchItemButton(Entity);
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingBenchRecipeButton)]
+pub struct BenchRecipeButtonOf(pub Entity);
+
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = BenchRecipeButtonOf, linked_spawn)]
+pub struct CorrespondingBenchRecipeButton(Entity);
+
 pub fn crafting_screen() -> impl Scene {
     bsn! {
         Node {
@@ -53,13 +99,37 @@ pub fn crafting_screen() -> impl Scene {
 
             Node
             CraftList
-            Children []
+            Children [],
+
+            // ---
+
+            Text::new("Bench")
+            TextColor::BLACK
+            TextFont { font_size: px(24.0) },
+
+            Node {
+                flex_direction: FlexDirection::Row,
+            }
+            Children [
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: px(250),
+                }
+                BenchItemList
+                Children [],
+
+                Node {
+                    flex_direction: FlexDirection::Column,
+                }
+                BenchRecipeList
+                Children [],
+            ]
         ]
     }
 }
 
 fn craft_base_button(recipe_entity: Entity, recipe: &Recipe) -> impl Scene + use<> {
-    let contents = craft_base_button_text(&recipe);
+    let contents = craft_button_text(recipe.base.to_string(), &recipe.resources);
 
     bsn! {
         Node {
@@ -87,21 +157,49 @@ fn craft_base_button(recipe_entity: Entity, recipe: &Recipe) -> impl Scene + use
     }
 }
 
-fn craft_base_button_text(recipe: &Recipe) -> impl SceneList + use<> {
+fn craft_rune_button(recipe_entity: Entity, recipe: &RuneRecipe) -> impl Scene + use<> {
+    let contents = craft_button_text(recipe.name.clone(), &recipe.resources);
+
+    bsn! {
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            border: UiRect::all(Val::Px(2.)),
+            min_height: Val::Px(25.0),
+            width: Val::Px(200.0),
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Px(8.0)),
+        }
+        BorderColor::all(Color::BLACK)
+
+        Button
+        Hovered
+        on(handle_rune_craft_button_click)
+        on(handle_rune_craft_button_hover)
+        on(handle_craft_button_out)
+
+        RuneCraftButtonOf(recipe_entity)
+        Children [
+            { contents }
+        ]
+    }
+}
+
+fn craft_button_text(name: String, resources: &[(StockKind, f64)]) -> impl SceneList + use<> {
     let mut scenes: Vec<Box<dyn Scene>> = Vec::with_capacity(3);
 
-    let base_text = recipe.base.to_string();
-    let resource_text = recipe.resources
+    let resource_text = resources
     .iter()
     .map(|&(stock, amount)| format!("{} - {}", stock, amount))
     .join("  ");
 
     scenes.push(Box::new(bsn! {
-        Text::new(base_text)
+        Text::new(name)
         TextColor::BLACK
     }));
 
-    if recipe.resources.len() > 0 {
+    if resources.len() > 0 {
         scenes.push(Box::new(bsn! {
             Node {
                 margin: UiRect::left(px(5))
@@ -131,6 +229,7 @@ fn handle_craft_button_hover(
     mut commands: Commands,
 
     db: Res<ItemDatabase>,
+    equipped_sets: EquippedSets,
 
     craft_button_of_query: Query<&CraftButtonOf>,
     recipe_query: Query<&Recipe>,
@@ -138,7 +237,39 @@ fn handle_craft_button_hover(
     let recipe = craft_button_of_query.get(event.entity).expect("Craft button must have CraftButtonOf component.").0;
     let recipe = recipe_query.get(recipe).expect("Entity of CraftButtonOf must have a Recipe component.");
 
-    let tooltip_content = spawn_item_details(commands.reborrow(), &db.create_basic(recipe.base));
+    let item = db.create_basic(recipe.base, recipe.item_level);
+    let tooltip_content = spawn_item_details(commands.reborrow(), &item, equipped_sets.details(&item));
+    commands.trigger(tooltip::ShowTooltip { content: tooltip_content });
+}
+
+fn handle_rune_craft_button_click(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    rune_craft_button_of_query: Query<&RuneCraftButtonOf>,
+) {
+    let recipe = rune_craft_button_of_query.get(event.entity).expect("Rune craft button must have RuneCraftButtonOf component.").0;
+
+    commands.trigger(CraftRuneRequest { recipe });
+}
+
+fn handle_rune_craft_button_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    rune_craft_button_of_query: Query<&RuneCraftButtonOf>,
+    rune_recipe_query: Query<&RuneRecipe>,
+) {
+    let recipe = rune_craft_button_of_query.get(event.entity).expect("Rune craft button must have RuneCraftButtonOf component.").0;
+    let recipe = rune_recipe_query.get(recipe).expect("Entity of RuneCraftButtonOf must have a RuneRecipe component.");
+
+    let tooltip_content = tooltip::spawn_tooltip_box(
+        commands.reborrow(),
+        recipe.name.clone(),
+        format!("Up to {}", recipe.modifier.kind.display_actual(recipe.modifier.max)),
+        "Goes to the inventory's runes, to socket into the item in the chosen slot.",
+    );
+
     commands.trigger(tooltip::ShowTooltip { content: tooltip_content });
 }
 
@@ -161,4 +292,176 @@ fn on_new_recipe(
             ChildOf({ *craft_list })
         });
     }
-}
\ No newline at end of file
+}
+
+fn on_new_rune_recipe(
+    mut commands: Commands,
+
+    rune_recipe_query: Query<(Entity, &RuneRecipe), Added<RuneRecipe>>,
+    craft_list: Single<Entity, With<CraftList>>,
+) {
+    for (entity, recipe) in rune_recipe_query.iter() {
+        commands.spawn_scene(bsn! {
+            craft_rune_button(entity, recipe)
+            ChildOf({ *craft_list })
+        });
+    }
+}
+
+fn bench_item_button(item_entity: Entity, item_name: String) -> impl Scene {
+    bsn! {
+        Node {
+            border: UiRect::all(Val::Px(2.)),
+            margin: UiRect::all(Val::Px(4.0)),
+        }
+        BorderColor::all(Color::BLACK)
+
+        Button
+        on(handle_bench_item_click)
+        on(handle_bench_item_hover)
+        on(handle_craft_button_out)
+
+        BenchItemButtonOf(item_entity)
+        Children [
+            Node {
+                border: px(1),
+                padding: px(2),
+            }
+            BorderColor::all(Color::BLACK)
+            Text(item_name)
+            TextColor::BLACK
+            ItemNameText(item_entity)
+        ]
+    }
+}
+
+fn bench_recipe_button(bench_recipe_entity: Entity, title: String, effect: String, bench_recipe: &BenchRecipe) -> impl Scene + use<> {
+    let resource_text = bench_recipe.resources
+    .iter()
+    .map(|&(stock, amount)| format!("{} - {}", stock, amount))
+    .join("  ");
+
+    bsn! {
+        Node {
+            display: Display::Flex,
+            flex_direction: FlexDirection::Column,
+            border: UiRect::all(Val::Px(2.)),
+            width: Val::Px(250.0),
+            align_items: AlignItems::Center,
+            margin: UiRect::all(Val::Px(4.0)),
+        }
+        BorderColor::all(Color::BLACK)
+
+        Button
+        on(handle_bench_recipe_click)
+
+        BenchRecipeButtonOf(bench_recipe_entity)
+        Children [
+            Text::new(title)
+            TextColor::BLACK,
+
+            Text::new(effect)
+            TextColor::BLACK
+            TextFont { font_size: px(12) },
+
+            Text::new(resource_text)
+            TextColor::BLACK
+            TextFont { font_size: px(12) },
+        ]
+    }
+}
+
+fn handle_bench_item_click(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    mut target_query: Query<(Entity, &mut BorderColor), With<BenchTarget>>,
+) {
+    for (previous_target, mut border_color) in target_query.iter_mut() {
+        *border_color = BorderColor::all(Color::BLACK);
+        commands.entity(previous_target).remove::<BenchTarget>();
+    }
+
+    commands.entity(event.entity).insert((BenchTarget, BorderColor::all(Color::WHITE)));
+}
+
+fn handle_bench_item_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    equipped_sets: EquippedSets,
+
+    bench_item_button_of_query: Query<&BenchItemButtonOf>,
+    item_query: Query<&AffixiveItem>,
+) {
+    let item = bench_item_button_of_query.get(event.entity).expect("Bench item button must have BenchItemButtonOf component.").0;
+    let item = item_query.get(item).expect("Entity of BenchItemButtonOf must have an AffixiveItem component.");
+
+    let tooltip_content = spawn_item_details(commands.reborrow(), item, equipped_sets.details(item));
+    commands.trigger(tooltip::ShowTooltip { content: tooltip_content });
+}
+
+fn handle_bench_recipe_click(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    target: Option<Single<&BenchItemButtonOf, With<BenchTarget>>>,
+    mut messages: MessageWriter<LogEntry>,
+
+    bench_recipe_button_of_query: Query<&BenchRecipeButtonOf>,
+) {
+    let Some(target) = target else {
+        messages.write(LogEntry::new("Choose an item on the bench first."));
+        return;
+    };
+
+    let bench_recipe = bench_recipe_button_of_query.get(event.entity).expect("Bench recipe button must have BenchRecipeButtonOf component.").0;
+
+    commands.trigger(BenchCraftRequest { bench_recipe, item: target.0 });
+}
+
+fn on_new_bench_item(
+    mut commands: Commands,
+
+    item_query: Query<(Entity, &AffixiveItem), Added<AffixiveItem>>,
+    bench_item_list: Single<Entity, With<BenchItemList>>,
+) {
+    for (entity, item) in item_query.iter() {
+        commands.spawn_scene(bsn! {
+            bench_item_button(entity, item.name().to_string())
+            ChildOf({ *bench_item_list })
+        });
+    }
+}
+
+fn on_new_bench_recipe(
+    mut commands: Commands,
+
+    db: Res<ItemDatabase>,
+
+    bench_recipe_query: Query<(Entity, &BenchRecipe), Added<BenchRecipe>>,
+    bench_recipe_list: Single<Entity, With<BenchRecipeList>>,
+) {
+    for (entity, bench_recipe) in bench_recipe_query.iter() {
+        let Some(family) = db.affix_family(bench_recipe.affix_kind, &bench_recipe.affix) else {
+            warn!("Bench recipe for unknown {} `{}`.", bench_recipe.affix_kind, bench_recipe.affix);
+            continue;
+        };
+
+        let Some(tier) = family.tiers.get((bench_recipe.tier as usize).saturating_sub(1)) else {
+            warn!("Bench recipe for missing tier {} of {} `{}`.", bench_recipe.tier, bench_recipe.affix_kind, bench_recipe.affix);
+            continue;
+        };
+
+        let title = format!("{} {} ({})", family.name, bench_recipe.tier, bench_recipe.affix_kind);
+        let mut effect = format!("Up to {}", family.kind.display_actual(tier.max));
+        if let Some(hybrid_kind) = family.hybrid_kind && let Some(hybrid) = tier.hybrid {
+            effect.push_str(&format!("\nUp to {}", hybrid_kind.display_actual(hybrid.max)));
+        }
+
+        commands.spawn_scene(bsn! {
+            bench_recipe_button(entity, title, effect, bench_recipe)
+            ChildOf({ *bench_recipe_list })
+        });
+    }
+}
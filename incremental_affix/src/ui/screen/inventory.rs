@@ -1,15 +1,47 @@
+use std::collections::HashMap;
+
+use bevy::input::keyboard::{Key, KeyboardInput};
 use bevy::prelude::*;
 use bevy::ui_widgets::{Activate, Button};
+use itertools::Itertools as _;
 
-use crate::incremental::item::affixive_item::PushAffixError;
-use crate::incremental::item::equipment::Equipped;
-use crate::incremental::item::item_database::ItemDatabase;
-use crate::incremental::item::{item_slot::{ItemSlot, ItemSlotTag}, craft::Crafted};
+use crate::incremental::item::currency::{CraftingCurrency, UseCurrencyRequest};
+use crate::incremental::item::equipment::{EquipRequest, Equipped, UnequipRequest, Unequipped};
+use crate::incremental::item::salvage::{DiscardRequest, SalvageRequest};
+use crate::incremental::item::durability::{RepairRequest, repair_cost};
+use crate::incremental::item::item_set::EquippedSets;
+use crate::incremental::item::rune::{Rune, RuneRecipe, SocketRuneRequest, UnsocketRuneRequest, unsocket_cost};
+use crate::incremental::item::inventory_view::{InventorySort, InventoryView};
+use crate::incremental::item::loadout::{BindLoadoutRequest, DeleteLoadoutRequest, EquipLoadoutRequest, Loadout, SaveLoadoutRequest};
+use crate::incremental::item::modifier::ModifierKind;
+use crate::incremental::item::{ItemObtained, item_database::ItemDatabase, item_slot::ItemSlot, craft::{Crafted, Recipe}};
 use crate::incremental::item::{affixive_item::{AffixiveItem, ItemTag}};
+use crate::incremental::action::Action;
 use crate::incremental::log::LogEntry;
-use crate::ui::tooltip::{HideTooltip, ShowTooltip};
-use crate::ui::item::spawn_item_details;
+use crate::incremental::stats::{PlayerActionsStats, StatId, StatSources};
+use crate::ui::tooltip::{HideTooltip, ShowTooltip, spawn_tooltip_box};
+use crate::ui::item::{ItemNameText, spawn_item_details};
 use crate::ui::screen::{Screen, screen_title};
+use crate::ui::stats::spawn_stat_changes;
+
+const SEARCH_PLACEHOLDER: &str = "Search affixes";
+
+pub struct InventoryScreenPlugin;
+
+impl Plugin for InventoryScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (
+            type_in_search,
+            update_view_controls,
+            apply_inventory_view,
+        ).chain())
+        .add_systems(Update, (on_new_item_slot, update_slot_nodes).chain())
+        .add_systems(Update, (on_new_loadout, update_loadout_bind_texts))
+        .add_systems(Update, on_new_rune)
+        ;
+    }
+}
 
 #[derive(Debug, Clone, Component, FromTemplate)]
 pub struct InventoryList(Entity);
@@ -21,12 +53,114 @@ impl InventoryList {
 }
 
 #[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = InventoryLine)]
 pub struct CorrespondingItem(Entity);
 
+/// Despawns the inventory line when the item is despawned.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = CorrespondingItem, linked_spawn)]
+pub struct InventoryLine(Entity);
+
 #[derive(Debug, Clone, Copy, Default, Component)]
 pub struct ActiveSlot;
 
-pub fn inventory_screen() -> impl Scene {
+/// Marker component for the [Node] that contains the slot nodes.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct SlotList;
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingSlotNode)]
+pub struct SlotNodeOf(pub Entity);
+
+/// Despawns the slot node when the item slot is no longer in the item definitions.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = SlotNodeOf, linked_spawn)]
+pub struct CorrespondingSlotNode(Entity);
+
+/// The text of a slot node showing the name of the item slot.
+#[derive(Debug, Clone, Component, FromTemplate)]
+pub struct SlotNameText(Entity);
+
+/// The crafting currency used by a button on an inventory line.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct CurrencyButton(CraftingCurrency);
+
+/// Marker component for the discard button of an inventory line.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct DiscardButton;
+
+/// Marker component for a discard button that was clicked once and discards the item when clicked again.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct ConfirmingDiscard;
+
+/// Part of the [InventoryView] a button cycles through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Component)]
+pub enum ViewControl {
+    #[default] // Needed to use in BSN.
+    Sort,
+    SortStat,
+    Tag,
+    ModifierKind,
+}
+
+/// The text of a [ViewControl] button.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct ViewControlText(ViewControl);
+
+/// Marker component for the search box.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct SearchBox;
+
+/// Marker component for the search box while it is being typed in.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct SearchFocused;
+
+/// Marker component for the text of the search box.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct SearchText;
+
+/// Marker component for the [Node] that contains the loadout lines.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct LoadoutList;
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingLoadoutLine)]
+pub struct LoadoutLineOf(pub Entity);
+
+/// Despawns the loadout line when the loadout is deleted.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = LoadoutLineOf, linked_spawn)]
+pub struct CorrespondingLoadoutLine(Entity);
+
+/// A button on an item slot that takes out the last socketed rune of its item.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct UnsocketButton {
+    /// Destroy the rune instead of paying to keep it.
+    shatter: bool,
+}
+
+/// Marker component for the [Node] that contains the rune lines.
+#[derive(Debug, Clone, Copy, Default, Component)]
+pub struct RuneList;
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingRuneLine)]
+pub struct RuneLineOf(pub Entity);
+
+/// Despawns the rune line when the rune is socketed.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = RuneLineOf, linked_spawn)]
+pub struct CorrespondingRuneLine(Entity);
+
+/// The text of a loadout line's button for binding it to an action.
+#[derive(Debug, Clone, Component, FromTemplate)]
+pub struct LoadoutBindText(Entity);
+
+pub fn inventory_screen(unequipped_items: Vec<(Entity, String)>) -> impl Scene {
+    let item_lines = unequipped_items.into_iter()
+    .map(|(item_entity, item_name)| inventory_item(item_entity, item_name))
+    .collect::<Vec<_>>();
+
     bsn! {
         Node {
             display: Display::None,
@@ -49,30 +183,82 @@ pub fn inventory_screen() -> impl Scene {
                     height: px(150)
                 }
                 BackgroundColor(Color::srgb_u8(137, 81, 41))
-                Children [
-                    #ToolSlot
-                    ActiveSlot
-                    slot(ItemSlotTag::Tool),
-
-                    #HuntSlot
-                    slot(ItemSlotTag::Hunt),
-                ]
+                SlotList
             ),
 
             // ---
 
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+            }
+            Children [
+                Text::new("Loadouts")
+                TextColor::BLACK,
+
+                line_button("Save")
+                on(on_activate_button_save_loadout)
+            ],
+
+            Node {
+                flex_direction: FlexDirection::Column,
+            }
+            LoadoutList,
+
+            // ---
+
+            Node {
+                flex_direction: FlexDirection::Row,
+                align_items: AlignItems::Center,
+            }
+            Children [
+                view_control(ViewControl::Sort),
+                view_control(ViewControl::SortStat),
+                view_control(ViewControl::Tag),
+                view_control(ViewControl::ModifierKind),
+
+                Node {
+                    border: px(1),
+                    margin: px(4),
+                    padding: px(2),
+                    min_width: px(160),
+                }
+                BorderColor::all(Color::BLACK)
+                Button
+                SearchBox
+                on(on_activate_search)
+                Children [
+                    Text::new(SEARCH_PLACEHOLDER)
+                    TextColor::BLACK
+                    SearchText
+                ]
+            ],
+
             #InventoryList
             Node {
                 flex_direction: FlexDirection::Column,
             }
             BackgroundColor(Color::srgb_u8(67, 111, 71))
+            Children [ { item_lines } ],
+
+            // ---
+
+            Text::new("Runes")
+            TextColor::BLACK,
+
+            Node {
+                flex_direction: FlexDirection::Column,
+            }
+            RuneList
         ]
     }
 }
 
-fn slot(slot_tag: ItemSlotTag) -> impl Scene {
+/// Locked slots are hidden until they are unlocked.
+fn slot(item_slot_entity: Entity, slot_name: String) -> impl Scene {
     bsn!{
         Node {
+            display: Display::None,
             flex_direction: FlexDirection::Column,
 
             box_sizing: BoxSizing::BorderBox,
@@ -91,14 +277,110 @@ fn slot(slot_tag: ItemSlotTag) -> impl Scene {
         on(on_out_hide_tooltip)
         on(on_slot_activate)
 
-        ItemSlot {
-            tag: slot_tag,
-            item: None,
-        }
+        SlotNodeOf(item_slot_entity)
 
         Children [
             #SlotName
-            Text::new(slot_tag.to_string())
+            Text(slot_name)
+            SlotNameText(item_slot_entity),
+
+            line_button("Unequip")
+            on(on_activate_button_unequip),
+
+            Node
+            Children [
+                line_button("R")
+                on(on_activate_button_slot_repair)
+                on(on_slot_repair_hover),
+
+                line_button("-R")
+                UnsocketButton
+                on(on_activate_button_unsocket)
+                on(on_unsocket_hover),
+
+                line_button("xR")
+                template_value(UnsocketButton { shatter: true })
+                on(on_activate_button_unsocket)
+                on(on_unsocket_hover),
+            ]
+        ]
+    }
+}
+
+fn view_control(control: ViewControl) -> impl Scene {
+    bsn! {
+        Node {
+            border: px(1),
+            margin: px(4),
+            padding: px(2),
+        }
+        BorderColor::all(Color::BLACK)
+        Button
+        template_value(control)
+        on(on_activate_view_control)
+        Children [
+            Text::new("")
+            TextColor::BLACK
+            template_value(ViewControlText(control))
+        ]
+    }
+}
+
+fn loadout_line(loadout_entity: Entity, loadout_name: String) -> impl Scene {
+    bsn! {
+        Node {
+            align_items: AlignItems::Center,
+        }
+        LoadoutLineOf(loadout_entity)
+
+        on(on_loadout_hover)
+        on(on_out_hide_tooltip)
+
+        Children [
+            Node {
+                width: px(100),
+            }
+            Text(loadout_name)
+            TextColor::BLACK,
+
+            line_button("Equip")
+            on(on_activate_button_equip_loadout),
+
+            Node {
+                border: px(1),
+                margin: px(4),
+            }
+            BorderColor::all(Color::BLACK)
+            Button
+            on(on_activate_button_bind_loadout)
+            Children [
+                Text::new("")
+                TextColor::BLACK
+                LoadoutBindText(loadout_entity)
+            ],
+
+            line_button("X")
+            on(on_activate_button_delete_loadout),
+        ]
+    }
+}
+
+fn rune_line(rune_entity: Entity, rune_name: String) -> impl Scene {
+    bsn! {
+        Node {
+            align_items: AlignItems::Center,
+        }
+        RuneLineOf(rune_entity)
+
+        on(on_rune_hover)
+        on(on_out_hide_tooltip)
+
+        Children [
+            line_button("Socket")
+            on(on_activate_button_socket),
+
+            Text(rune_name)
+            TextColor::BLACK,
         ]
     }
 }
@@ -121,7 +403,70 @@ pub fn on_item_craft(
     log_event_writer.write(LogEntry(format!("Crafted '{}'", item.name())));
 }
 
+pub fn on_item_obtained(
+    event: On<ItemObtained>,
+    mut commands: Commands,
+
+    inventory_list: Single<&InventoryList>,
+
+    item_query: Query<&AffixiveItem>,
+
+    mut log_event_writer: MessageWriter<LogEntry>,
+) {
+    let item = item_query.get(event.item).unwrap();
+    commands.spawn_scene(bsn! {
+        inventory_item(event.item, item.name().to_string())
+        ChildOf({ inventory_list.get() })
+    });
+    log_event_writer.write(LogEntry(format!("Obtained '{}'", item.name())));
+}
+
 pub fn inventory_item(item_entity: Entity, item_name: String) -> impl Scene {
+    let mut contents: Vec<Box<dyn Scene>> = Vec::with_capacity(CraftingCurrency::LIST.len() + 4);
+
+    contents.push(Box::new(bsn! {
+        line_button("E")
+        on(on_activate_button_equip)
+    }));
+
+    for currency in CraftingCurrency::LIST.iter().copied() {
+        contents.push(Box::new(bsn! {
+            line_button(currency_label(currency))
+            template_value(CurrencyButton(currency))
+            on(on_activate_button_currency)
+            on(on_currency_hover)
+        }));
+    }
+
+    contents.push(Box::new(bsn! {
+        line_button("R")
+        on(on_activate_button_repair)
+        on(on_repair_hover)
+    }));
+
+    contents.push(Box::new(bsn! {
+        line_button("S")
+        on(on_activate_button_salvage)
+    }));
+
+    contents.push(Box::new(bsn! {
+        line_button("X")
+        DiscardButton
+        on(on_activate_button_discard)
+        on(on_discard_out)
+    }));
+
+    contents.push(Box::new(bsn! {
+        Node {
+            border: px(1),
+            padding: px(2),
+        }
+        BorderColor::all(Color::BLACK)
+        Text(item_name)
+        TextColor::BLACK
+        ItemNameText(item_entity)
+    }));
+
     bsn! {
         #Line
         Node
@@ -131,18 +476,21 @@ pub fn inventory_item(item_entity: Entity, item_name: String) -> impl Scene {
         on(on_out_hide_tooltip)
 
         Children [
-            line_button("E")
-            on(on_activate_button_equip),
-
-            line_button("R")
-            on(on_activate_button_roll),
-
-            Text(item_name)
-            TextColor::BLACK
+            { contents }
         ]
     }
 }
 
+fn currency_label(currency: CraftingCurrency) -> &'static str {
+    match currency {
+        CraftingCurrency::AddAffix => "+A",
+        CraftingCurrency::RerollAffixes => "RA",
+        CraftingCurrency::RemoveAffix => "-A",
+        CraftingCurrency::RerollValues => "RV",
+        CraftingCurrency::UpgradeQuality => "+Q",
+    }
+}
+
 pub fn line_button(text: &'static str) -> impl Scene {
     bsn!{
         Node {
@@ -158,135 +506,922 @@ pub fn line_button(text: &'static str) -> impl Scene {
     }
 }
 
+/// Equips the item into the active slot, or the first slot that accepts it if the active slot does not.
 fn on_activate_button_equip(
     event: On<Activate>,
     mut commands: Commands,
 
-    active_slot: Single<(&ActiveSlot, Entity)>,
-    inventory_screen: Single<&InventoryList>,
-    item_db: Res<ItemDatabase>,
+    active_slot: Single<&SlotNodeOf, With<ActiveSlot>>,
 
     parent_query: Query<&ChildOf>,
     corresponding_item_query: Query<&CorrespondingItem>,
-    item_query: Query<&AffixiveItem>,
-    mut item_slot_query: Query<&mut ItemSlot>
 ) {
     let item_node = parent_query.get(event.entity).unwrap().parent();
     let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
 
-    let item = item_query.get(corresponding_item)
-    .expect("Corresponding item entity must have an item component.");
+    commands.trigger(EquipRequest { item: corresponding_item, preferred_slot: Some(active_slot.0) });
+}
+
+/// Removes the inventory line of the item, however it was equipped.
+pub fn on_item_equipped(
+    event: On<Equipped>,
+    mut commands: Commands,
 
-    let mut item_slot = item_slot_query.get_mut(active_slot.1)
-    .expect("Active slot resource must have an item slot component.");
+    inventory_line_query: Query<&InventoryLine>,
+) {
+    let Ok(inventory_line) = inventory_line_query.get(event.item) else { return; };
+    commands.entity(inventory_line.0).despawn();
+}
 
-    let item_tag = ItemTag::from(item_slot.tag);
+/// Returns the item in the slot to the inventory.
+fn on_activate_button_unequip(
+    event: On<Activate>,
+    mut commands: Commands,
 
-    if !item_db.item_has_tag(item, item_tag) {
-        return;
-    }
+    parent_query: Query<&ChildOf>,
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let slot_node = parent_query.get(event.entity).unwrap().parent();
+    let item_slot_entity = slot_node_query.get(slot_node).expect("Item slot node must have a SlotNodeOf component.").0;
+    let item_slot = item_slot_query.get(item_slot_entity).expect("Entity of SlotNodeOf must have an ItemSlot component.");
 
-    let previous_item = item_slot.item.replace(corresponding_item);
+    commands.trigger(UnequipRequest { slot: item_slot.id.clone() });
+}
 
-    if let Some(previous_item_entity) = previous_item {
-        let previous_item = item_query.get(previous_item_entity)
-        .expect("Item entity in an item slot must have an item entity.");
+pub fn on_item_unequipped(
+    event: On<Unequipped>,
+    mut commands: Commands,
 
-        let name = previous_item.name().to_string();
+    inventory_list: Single<&InventoryList>,
 
-        commands.spawn_scene(bsn!{
-            inventory_item(previous_item_entity, name.to_string())
-            ChildOf({ inventory_screen.get() })
-        });
-    }
-    commands.entity(item_node).despawn();
+    item_query: Query<&AffixiveItem>,
+) {
+    let item = item_query.get(event.item).expect("Unequipped item entity must have an item component.");
+    commands.spawn_scene(bsn! {
+        inventory_item(event.item, item.name().into_owned())
+        ChildOf({ inventory_list.get() })
+    });
+}
+
+/// The item in the slot of a button on the item slot node's button row.
+fn button_slot_item(
+    button: Entity,
 
-    commands.trigger(Equipped { item: corresponding_item });
+    parent_query: &Query<&ChildOf>,
+    slot_node_query: &Query<&SlotNodeOf>,
+    item_slot_query: &Query<&ItemSlot>,
+) -> Option<Entity> {
+    let button_row = parent_query.get(button).unwrap().parent();
+    let slot_node = parent_query.get(button_row).unwrap().parent();
+    let item_slot_entity = slot_node_query.get(slot_node).expect("Item slot node must have a SlotNodeOf component.").0;
+
+    item_slot_query.get(item_slot_entity).ok()
+    .and_then(|item_slot| item_slot.item)
 }
 
-fn on_activate_button_roll(
+/// Takes the last socketed rune out of the item in the slot.
+fn on_activate_button_unsocket(
     event: On<Activate>,
+    mut commands: Commands,
 
-    db: Res<ItemDatabase>,
-
-    mut log_writer: MessageWriter<LogEntry>,
+    mut messages: MessageWriter<LogEntry>,
 
     parent_query: Query<&ChildOf>,
-    corresponding_item_query: Query<&CorrespondingItem>,
-    mut item_query: Query<&mut AffixiveItem>,
+    unsocket_button_query: Query<&UnsocketButton>,
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
+    item_query: Query<&AffixiveItem>,
 ) {
-    let item_node = parent_query.get(event.entity).unwrap().parent();
-    let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+    let shatter = unsocket_button_query.get(event.entity).expect("Unsocket button must have an UnsocketButton component.").shatter;
 
-    let mut item = item_query.get_mut(corresponding_item)
-    .expect("Corresponding item entity must have an item component.");
+    let Some(item_entity) = button_slot_item(event.entity, &parent_query, &slot_node_query, &item_slot_query) else {
+        messages.write(LogEntry::new("Nothing is equipped there."));
+        return;
+    };
 
-    item.increase_quality_to(1);
-    match db.try_push_random_prefix(&mut item) {
-        Ok(_) => {},
-        Err(PushAffixError::AffixiveItemIsFixed) => {
-            log_writer.write(LogEntry::new("You cannot modify the affixes of this."));
-        },
-        Err(PushAffixError::AffixiveItemQualityTooLow) => {
-            log_writer.write(LogEntry::new("Cannot add prefix. Item quality too low."));
-        },
-    }
+    let item = item_query.get(item_entity).expect("Item entity must have item component.");
 
-    match db.try_push_random_suffix(&mut item) {
-        Ok(_) => {},
-        Err(PushAffixError::AffixiveItemIsFixed) => {
-            log_writer.write(LogEntry::new("You cannot modify the affixes of this."));
-        },
-        Err(PushAffixError::AffixiveItemQualityTooLow) => {
-            log_writer.write(LogEntry::new("Cannot add prefix. Item quality too low."));
-        },
-    }
+    let Some(socket) = item.runes().len().checked_sub(1) else {
+        messages.write(format!("'{}' has no runes.", item.name()).into());
+        return;
+    };
+
+    commands.trigger(UnsocketRuneRequest { item: item_entity, socket, shatter });
 }
 
-fn on_inventory_hover(
-    event: On<Pointer<Over>>,
+fn on_unsocket_hover(
+    mut event: On<Pointer<Over>>,
     mut commands: Commands,
 
-    corresponding_item_query: Query<&CorrespondingItem>,
+    parent_query: Query<&ChildOf>,
+    unsocket_button_query: Query<&UnsocketButton>,
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
     item_query: Query<&AffixiveItem>,
+    rune_recipe_query: Query<&RuneRecipe>,
 ) {
-    let item_entity = corresponding_item_query.get(event.entity)
-    .expect("Corresponding item must be on this entity.").0;
+    // Show the rune instead of the item of the slot.
+    event.propagate(false);
 
-    let item = item_query.get(item_entity)
-    .expect("Item entity must have item component.");
+    let shatter = unsocket_button_query.get(event.entity).expect("Unsocket button must have an UnsocketButton component.").shatter;
+
+    let last_rune = button_slot_item(event.entity, &parent_query, &slot_node_query, &item_slot_query)
+    .and_then(|item_entity| item_query.get(item_entity).ok())
+    .and_then(|item| item.runes().last());
+
+    let content = match (last_rune, shatter) {
+        (None, _) => spawn_tooltip_box(commands.reborrow(), "No rune to take out", "", ""),
+
+        (Some(rune), true) => spawn_tooltip_box(commands.reborrow(), format!("Shatter '{}'", rune.name()), "Destroys the rune to free its socket.", ""),
+
+        (Some(rune), false) => {
+            match rune_recipe_query.iter().find(|recipe| recipe.rune == rune.id) {
+                Some(recipe) => {
+                    let cost = unsocket_cost(recipe)
+                    .iter()
+                    .map(|&(stock, amount)| format!("{} - {}", stock, amount))
+                    .join("  ");
+
+                    spawn_tooltip_box(commands.reborrow(), format!("Take out '{}'", rune.name()), "Returns the rune to the inventory.", cost)
+                },
+                None => spawn_tooltip_box(commands.reborrow(), format!("Take out '{}'", rune.name()), "There is no recipe to keep this rune with. It can only be shattered.", ""),
+            }
+        },
+    };
 
-    let content = spawn_item_details(commands.reborrow(), item);
     commands.trigger(ShowTooltip { content });
 }
 
-fn on_slot_hover(
+/// Socket the rune into the item in the active slot.
+fn on_activate_button_socket(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    active_slot: Single<&SlotNodeOf, With<ActiveSlot>>,
+    mut messages: MessageWriter<LogEntry>,
+
+    parent_query: Query<&ChildOf>,
+    rune_line_query: Query<&RuneLineOf>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let rune_line = parent_query.get(event.entity).unwrap().parent();
+    let rune = rune_line_query.get(rune_line).expect("Rune line must have a RuneLineOf component.").0;
+
+    let item = item_slot_query.get(active_slot.0).ok()
+    .and_then(|item_slot| item_slot.item);
+
+    let Some(item) = item else {
+        messages.write(LogEntry::new("Equip an item in the chosen slot to socket runes into it."));
+        return;
+    };
+
+    commands.trigger(SocketRuneRequest { item, rune });
+}
+
+fn on_rune_hover(
     event: On<Pointer<Over>>,
     mut commands: Commands,
 
-    item_slot: Query<&ItemSlot>,
-    item_query: Query<&AffixiveItem>,
+    rune_line_query: Query<&RuneLineOf>,
+    rune_query: Query<&Rune>,
 ) {
-    let item_slot = item_slot.get(event.entity).expect("Item slot node must have an item slot component.");
-    let Some(item_entity) = item_slot.item else { return /* if no item, no tooltip to show */; };
-    let item = item_query.get(item_entity).expect("Item entity must have item component.");
-    let content = spawn_item_details(commands.reborrow(), item);
+    let rune_entity = rune_line_query.get(event.entity).expect("Rune line must have a RuneLineOf component.").0;
+    let rune = rune_query.get(rune_entity).expect("Entity of RuneLineOf must have a Rune component.");
+
+    let content = spawn_tooltip_box(commands.reborrow(), rune.name().to_string(), rune.display(), "Sockets into the item in the chosen slot.");
     commands.trigger(ShowTooltip { content });
 }
 
-fn on_out_hide_tooltip(
-    _event: On<Pointer<Out>>,
+fn on_activate_button_repair(
+    event: On<Activate>,
     mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    corresponding_item_query: Query<&CorrespondingItem>,
 ) {
-    commands.trigger(HideTooltip);
+    let item_node = parent_query.get(event.entity).unwrap().parent();
+    let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+
+    commands.trigger(RepairRequest { item: corresponding_item });
 }
 
-fn on_slot_activate(
+fn on_activate_button_salvage(
     event: On<Activate>,
     mut commands: Commands,
 
-    active_slot: Single<(&ActiveSlot, Entity)>,
+    parent_query: Query<&ChildOf>,
+    corresponding_item_query: Query<&CorrespondingItem>,
 ) {
-    commands.get_entity(active_slot.1).unwrap().remove::<ActiveSlot>();
-    commands.get_entity(event.entity).unwrap().insert(ActiveSlot);
-}
\ No newline at end of file
+    let item_node = parent_query.get(event.entity).unwrap().parent();
+    let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+
+    commands.trigger(SalvageRequest { item: corresponding_item });
+}
+
+/// The first click asks for confirmation, the second discards the item.
+fn on_activate_button_discard(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    corresponding_item_query: Query<&CorrespondingItem>,
+    confirming_query: Query<(), With<ConfirmingDiscard>>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
+) {
+    if confirming_query.contains(event.entity) {
+        let item_node = parent_query.get(event.entity).unwrap().parent();
+        let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+
+        commands.trigger(DiscardRequest { item: corresponding_item });
+        return;
+    }
+
+    commands.entity(event.entity).insert(ConfirmingDiscard);
+    set_button_text(event.entity, "Sure?", &children_query, &mut text_query);
+}
+
+/// Moving the pointer away cancels the confirmation.
+fn on_discard_out(
+    event: On<Pointer<Out>>,
+    mut commands: Commands,
+
+    confirming_query: Query<(), (With<DiscardButton>, With<ConfirmingDiscard>)>,
+    children_query: Query<&Children>,
+    mut text_query: Query<&mut Text>,
+) {
+    if !confirming_query.contains(event.entity) {
+        return;
+    }
+
+    commands.entity(event.entity).remove::<ConfirmingDiscard>();
+    set_button_text(event.entity, "X", &children_query, &mut text_query);
+}
+
+fn set_button_text(
+    button: Entity,
+    text: &str,
+
+    children_query: &Query<&Children>,
+    text_query: &mut Query<&mut Text>,
+) {
+    let children = children_query.get(button).expect("Line button must have a text child.");
+    for &child in children {
+        if let Ok(mut button_text) = text_query.get_mut(child) {
+            button_text.0 = text.to_string();
+        }
+    }
+}
+
+fn on_activate_button_currency(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    corresponding_item_query: Query<&CorrespondingItem>,
+    currency_button_query: Query<&CurrencyButton>,
+) {
+    let item_node = parent_query.get(event.entity).unwrap().parent();
+    let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+    let currency = currency_button_query.get(event.entity)
+    .expect("Currency button must have a CurrencyButton component.").0;
+
+    commands.trigger(UseCurrencyRequest { item: corresponding_item, currency });
+}
+
+fn on_currency_hover(
+    mut event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    currency_button_query: Query<&CurrencyButton>,
+) {
+    // Show the currency instead of the item of the line.
+    event.propagate(false);
+
+    let currency = currency_button_query.get(event.entity)
+    .expect("Currency button must have a CurrencyButton component.").0;
+
+    let cost = currency.cost()
+    .iter()
+    .map(|&(stock, amount)| format!("{} - {}", stock, amount))
+    .join("  ");
+
+    let content = spawn_tooltip_box(commands.reborrow(), currency.to_string(), currency.description(), cost);
+
+    commands.trigger(ShowTooltip { content });
+}
+
+fn on_repair_hover(
+    mut event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    corresponding_item_query: Query<&CorrespondingItem>,
+    item_query: Query<&AffixiveItem>,
+    recipe_query: Query<&Recipe>,
+) {
+    // Show the repair instead of the item of the line.
+    event.propagate(false);
+
+    let item_node = parent_query.get(event.entity).unwrap().parent();
+    let corresponding_item = corresponding_item_query.get(item_node).unwrap().0;
+    let item = item_query.get(corresponding_item).expect("Corresponding item must have an AffixiveItem component.");
+
+    let content = spawn_repair_tooltip(commands.reborrow(), item, &recipe_query);
+    commands.trigger(ShowTooltip { content });
+}
+
+/// Repairs the item in the slot.
+fn on_activate_button_slot_repair(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    mut messages: MessageWriter<LogEntry>,
+
+    parent_query: Query<&ChildOf>,
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let Some(item) = button_slot_item(event.entity, &parent_query, &slot_node_query, &item_slot_query) else {
+        messages.write(LogEntry::new("Nothing is equipped there."));
+        return;
+    };
+
+    commands.trigger(RepairRequest { item });
+}
+
+fn on_slot_repair_hover(
+    mut event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
+    item_query: Query<&AffixiveItem>,
+    recipe_query: Query<&Recipe>,
+) {
+    // Show the repair instead of the item of the slot.
+    event.propagate(false);
+
+    let item = button_slot_item(event.entity, &parent_query, &slot_node_query, &item_slot_query)
+    .and_then(|item_entity| item_query.get(item_entity).ok());
+
+    let content = match item {
+        Some(item) => spawn_repair_tooltip(commands.reborrow(), item, &recipe_query),
+        None => spawn_tooltip_box(commands.reborrow(), "Repair", "Nothing is equipped there.", ""),
+    };
+
+    commands.trigger(ShowTooltip { content });
+}
+
+fn spawn_repair_tooltip(commands: Commands, item: &AffixiveItem, recipe_query: &Query<&Recipe>) -> Entity {
+    let recipe = recipe_query.iter().find(|recipe| recipe.base == item.base());
+
+    let description = match (item.durability(), recipe) {
+        (None, _) => "This item does not wear out.".to_string(),
+        (Some(_), None) => "There is no recipe to repair this item with.".to_string(),
+        (Some(durability), Some(_)) => format!("Restore durability ({}).", durability),
+    };
+
+    let cost = recipe.map(|recipe| repair_cost(item, recipe))
+    .unwrap_or_default()
+    .iter()
+    .map(|&(stock, amount)| format!("{} - {}", stock, amount))
+    .join("  ");
+
+    spawn_tooltip_box(commands, "Repair", description, cost)
+}
+
+/// Shows the item beside the item equipped in the slot it would go into,
+/// and how equipping it would change the player's stats.
+fn on_inventory_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    player_actions_stats: Res<PlayerActionsStats>,
+    stat_sources: StatSources,
+    equipped_sets: EquippedSets,
+
+    corresponding_item_query: Query<&CorrespondingItem>,
+    item_query: Query<&AffixiveItem>,
+    item_slot_query: Query<&ItemSlot>,
+) {
+    let item_entity = corresponding_item_query.get(event.entity)
+    .expect("Corresponding item must be on this entity.").0;
+
+    let item = item_query.get(item_entity)
+    .expect("Item entity must have item component.");
+
+    let content = commands.spawn(Node {
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }).id();
+
+    let items_row = commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Row,
+            align_items: AlignItems::FlexStart,
+            ..default()
+        },
+        ChildOf(content),
+    )).id();
+
+    let item_details = spawn_item_details(commands.reborrow(), item, equipped_sets.details(item));
+    commands.entity(item_details).insert(ChildOf(items_row));
+
+    let equipped_item = item_slot_query.iter()
+    .find(|item_slot| item_slot.accepts(item))
+    .and_then(|item_slot| item_slot.item)
+    .and_then(|equipped_item| item_query.get(equipped_item).ok());
+
+    if let Some(equipped_item) = equipped_item {
+        let equipped_column = commands.spawn((
+            Node {
+                flex_direction: FlexDirection::Column,
+                margin: px(4).left(),
+                ..default()
+            },
+            BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+            children![(
+                Text::new("Equipped"),
+                TextFont { font_size: FontSize::Px(12.0), ..default() },
+            )],
+            ChildOf(items_row),
+        )).id();
+
+        let equipped_details = spawn_item_details(commands.reborrow(), equipped_item, equipped_sets.details(equipped_item));
+        commands.entity(equipped_details).insert(ChildOf(equipped_column));
+    }
+
+    if let Some(equipped_stats) = stat_sources.calculate_equipping(item_entity) {
+        let stat_changes = spawn_stat_changes(commands.reborrow(), &player_actions_stats, &equipped_stats);
+        commands.entity(stat_changes).insert(ChildOf(content));
+    }
+
+    commands.trigger(ShowTooltip { content });
+}
+
+fn on_slot_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    equipped_sets: EquippedSets,
+
+    slot_node_query: Query<&SlotNodeOf>,
+    item_slot_query: Query<&ItemSlot>,
+    item_query: Query<&AffixiveItem>,
+) {
+    let item_slot_entity = slot_node_query.get(event.entity).expect("Item slot node must have a SlotNodeOf component.").0;
+    let item_slot = item_slot_query.get(item_slot_entity)
+    .expect("Entity of SlotNodeOf must have an ItemSlot component.");
+    let Some(item_entity) = item_slot.item else { return /* if no item, no tooltip to show */; };
+    let item = item_query.get(item_entity).expect("Item entity must have item component.");
+    let content = spawn_item_details(commands.reborrow(), item, equipped_sets.details(item));
+    commands.trigger(ShowTooltip { content });
+}
+
+fn on_out_hide_tooltip(
+    _event: On<Pointer<Out>>,
+    mut commands: Commands,
+) {
+    commands.trigger(HideTooltip);
+}
+
+fn on_slot_activate(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    active_slot: Single<(&ActiveSlot, Entity)>,
+) {
+    commands.get_entity(active_slot.1).unwrap().remove::<ActiveSlot>();
+    commands.get_entity(event.entity).unwrap().insert(ActiveSlot);
+}
+/// The value after the current one in the list, wrapping around.
+fn cycle<T: Copy + PartialEq>(list: &[T], current: T) -> T {
+    let index = list.iter().position(|&value| value == current).map_or(0, |index| (index + 1) % list.len());
+    list[index]
+}
+
+/// Like [cycle], with `None` before the first value.
+fn cycle_optional<T: Copy + PartialEq>(list: &[T], current: Option<T>) -> Option<T> {
+    match current {
+        None => list.first().copied(),
+        Some(current) => list.iter().position(|&value| value == current).and_then(|index| list.get(index + 1)).copied(),
+    }
+}
+
+fn on_activate_view_control(
+    event: On<Activate>,
+
+    mut inventory_view: ResMut<InventoryView>,
+
+    view_control_query: Query<&ViewControl>,
+) {
+    let control = *view_control_query.get(event.entity).expect("View control button must have a ViewControl component.");
+
+    match control {
+        ViewControl::Sort => inventory_view.sort = cycle(InventorySort::LIST, inventory_view.sort),
+        ViewControl::SortStat => inventory_view.sort_stat = cycle(StatId::LIST, inventory_view.sort_stat),
+        ViewControl::Tag => inventory_view.tag = cycle_optional(ItemTag::LIST, inventory_view.tag),
+        ViewControl::ModifierKind => inventory_view.modifier_kind = cycle_optional(ModifierKind::LIST, inventory_view.modifier_kind),
+    }
+}
+
+fn on_activate_search(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    focused_query: Query<(), With<SearchFocused>>,
+    mut border_query: Query<&mut BorderColor>,
+) {
+    let mut border_color = border_query.get_mut(event.entity).expect("Search box must have a border.");
+
+    if focused_query.contains(event.entity) {
+        commands.entity(event.entity).remove::<SearchFocused>();
+        *border_color = BorderColor::all(Color::BLACK);
+    } else {
+        commands.entity(event.entity).insert(SearchFocused);
+        *border_color = BorderColor::all(Color::WHITE);
+    }
+}
+
+/// Types into the search box while it is focused.
+///
+/// Enter, Escape, or leaving the inventory screen stops typing.
+fn type_in_search(
+    mut commands: Commands,
+    mut keyboard_inputs: MessageReader<KeyboardInput>,
+
+    mut inventory_view: ResMut<InventoryView>,
+
+    search_box: Option<Single<(Entity, &mut BorderColor), (With<SearchBox>, With<SearchFocused>)>>,
+    screen_query: Query<(&Node, &Screen), Without<Button>>,
+) {
+    let Some(search_box) = search_box else {
+        keyboard_inputs.clear();
+        return;
+    };
+    let (search_box_entity, mut border_color) = search_box.into_inner();
+
+    let inventory_visible = screen_query.iter()
+    .any(|(node, &screen)| screen == Screen::Inventory && node.display != Display::None);
+
+    let mut unfocus = !inventory_visible;
+
+    for input in keyboard_inputs.read() {
+        if unfocus || !input.state.is_pressed() {
+            continue;
+        }
+
+        match input.logical_key {
+            Key::Enter | Key::Escape => unfocus = true,
+            Key::Backspace => { inventory_view.search.pop(); },
+            _ => if let Some(ref text) = input.text {
+                inventory_view.search.extend(text.chars().filter(|c| !c.is_control()));
+            },
+        }
+    }
+
+    if unfocus {
+        commands.entity(search_box_entity).remove::<SearchFocused>();
+        *border_color = BorderColor::all(Color::BLACK);
+    }
+}
+
+fn update_view_controls(
+    inventory_view: Res<InventoryView>,
+
+    added_text_query: Query<(), Or<(Added<ViewControlText>, Added<SearchText>)>>,
+    mut view_control_text_query: Query<(&ViewControlText, &mut Text), Without<SearchText>>,
+    mut search_text: Single<&mut Text, With<SearchText>>,
+) {
+    if !inventory_view.is_changed() && added_text_query.is_empty() {
+        return;
+    }
+
+    for (&ViewControlText(control), mut text) in view_control_text_query.iter_mut() {
+        text.0 = match control {
+            ViewControl::Sort => format!("Sort: {}", inventory_view.sort),
+            ViewControl::SortStat => format!("Stat: {}", inventory_view.sort_stat),
+            ViewControl::Tag => format!("Tag: {}", inventory_view.tag.map_or("Any".to_string(), |tag| tag.to_string())),
+            ViewControl::ModifierKind => format!("Modifier: {}", inventory_view.modifier_kind.map_or("Any".to_string(), |kind| kind.to_string())),
+        };
+    }
+
+    search_text.0 = if inventory_view.search.is_empty() {
+        SEARCH_PLACEHOLDER.to_string()
+    } else {
+        inventory_view.search.clone()
+    };
+}
+
+/// Values of the sort stat if each item were equipped, so sorting by stat does not recalculate every item's stats
+/// whenever any item changes, such as when equipped items wear.
+#[derive(Debug, Default)]
+struct SortStatValues {
+    stat: StatId,
+
+    /// The player's value of the stat the values were calculated with.
+    player_value: Option<f64>,
+
+    values: HashMap<Entity, Option<f64>>,
+}
+
+/// Hides the inventory lines of items the view filters out and orders the rest as the view sorts them.
+///
+/// Only the inventory lines are reordered, never the items.
+fn apply_inventory_view(
+    mut commands: Commands,
+
+    inventory_view: Res<InventoryView>,
+    player_actions_stats: Res<PlayerActionsStats>,
+    stat_sources: StatSources,
+
+    inventory_list: Single<&InventoryList>,
+    children_query: Query<&Children>,
+    added_line_query: Query<(), Added<CorrespondingItem>>,
+    changed_item_query: Query<Entity, Changed<AffixiveItem>>,
+    mut line_query: Query<(&CorrespondingItem, &mut Node)>,
+    item_query: Query<&AffixiveItem>,
+
+    mut sort_stat_values: Local<SortStatValues>,
+) {
+    let sort_stat = inventory_view.sort_stat;
+    let player_value = player_actions_stats.get(sort_stat);
+
+    // Items are only worth a different amount of the stat if the player's own value of it changed.
+    let stats_changed = inventory_view.sort == InventorySort::Stat
+    && (sort_stat_values.stat != sort_stat || sort_stat_values.player_value != Some(player_value));
+
+    if !inventory_view.is_changed() && !stats_changed && added_line_query.is_empty() && changed_item_query.is_empty() {
+        return;
+    }
+
+    let Ok(lines) = children_query.get(inventory_list.get()) else { return; };
+    let lines = lines.to_vec();
+
+    let line_items = lines.iter()
+    .filter_map(|&line| line_query.get(line).ok().map(|(corresponding_item, _)| (line, corresponding_item.0)))
+    .collect::<Vec<_>>();
+
+    let items = line_items.iter()
+    .filter_map(|&(_, item_entity)| item_query.get(item_entity).ok().map(|item| (item_entity, item)));
+
+    if inventory_view.sort == InventorySort::Stat {
+        // Items changed while sorting some other way were never forgotten, so start over.
+        if stats_changed || inventory_view.is_changed() {
+            *sort_stat_values = SortStatValues { stat: sort_stat, player_value: Some(player_value), values: HashMap::new() };
+        }
+
+        for item_entity in changed_item_query.iter() {
+            sort_stat_values.values.remove(&item_entity);
+        }
+
+        sort_stat_values.values.retain(|item_entity, _| line_items.iter().any(|&(_, line_item)| line_item == *item_entity));
+
+        for &(_, item_entity) in line_items.iter() {
+            sort_stat_values.values.entry(item_entity)
+            .or_insert_with(|| stat_sources.calculate_equipping(item_entity).map(|stats| stats.get(sort_stat)));
+        }
+    }
+
+    let shown_items = inventory_view.arrange(items, |item_entity| {
+        sort_stat_values.values.get(&item_entity).copied().flatten()
+    });
+
+    let shown_lines = shown_items.iter()
+    .filter_map(|&item_entity| line_items.iter().find(|&&(_, line_item)| line_item == item_entity))
+    .map(|&(line, _)| line);
+
+    let hidden_lines = line_items.iter()
+    .filter(|&&(_, item_entity)| !shown_items.contains(&item_entity))
+    .map(|&(line, _)| line);
+
+    let ordered_lines = shown_lines.chain(hidden_lines).collect::<Vec<_>>();
+
+    for &(line, item_entity) in line_items.iter() {
+        let (_, mut node) = line_query.get_mut(line).expect("Inventory line must have a Node component.");
+        let display = if shown_items.contains(&item_entity) { Display::Flex } else { Display::None };
+
+        if node.display != display {
+            node.display = display;
+        }
+    }
+
+    if ordered_lines != lines {
+        commands.entity(inventory_list.get()).replace_children(&ordered_lines);
+    }
+}
+
+fn on_new_rune(
+    mut commands: Commands,
+
+    rune_query: Query<(Entity, &Rune), Added<Rune>>,
+    rune_list: Single<Entity, With<RuneList>>,
+) {
+    for (entity, rune) in rune_query.iter() {
+        commands.spawn_scene(bsn! {
+            rune_line(entity, rune.name().to_string())
+            ChildOf({ *rune_list })
+        });
+    }
+}
+
+fn on_new_loadout(
+    mut commands: Commands,
+
+    loadout_query: Query<(Entity, &Loadout), Added<Loadout>>,
+    loadout_list: Single<Entity, With<LoadoutList>>,
+) {
+    for (entity, loadout) in loadout_query.iter() {
+        commands.spawn_scene(bsn! {
+            loadout_line(entity, loadout.name.clone())
+            ChildOf({ *loadout_list })
+        });
+    }
+}
+
+fn update_loadout_bind_texts(
+    loadout_query: Query<&Loadout>,
+    changed_loadout_query: Query<(), Changed<Loadout>>,
+    added_text_query: Query<(), Added<LoadoutBindText>>,
+    mut bind_text_query: Query<(&LoadoutBindText, &mut Text)>,
+) {
+    if changed_loadout_query.is_empty() && added_text_query.is_empty() {
+        return;
+    }
+
+    for (&LoadoutBindText(loadout_entity), mut text) in bind_text_query.iter_mut() {
+        let Ok(loadout) = loadout_query.get(loadout_entity) else { continue; };
+        text.0 = format!("For: {}", loadout.action.map_or("Nothing".to_string(), |action| action.to_string()));
+    }
+}
+
+fn on_activate_button_save_loadout(
+    _event: On<Activate>,
+    mut commands: Commands,
+) {
+    commands.trigger(SaveLoadoutRequest);
+}
+
+/// The loadout of the loadout line that the button is on.
+fn button_loadout(button: Entity, parent_query: &Query<&ChildOf>, loadout_line_query: &Query<&LoadoutLineOf>) -> Entity {
+    let loadout_line = parent_query.get(button).unwrap().parent();
+    loadout_line_query.get(loadout_line).expect("Loadout line must have a LoadoutLineOf component.").0
+}
+
+fn on_activate_button_equip_loadout(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    loadout_line_query: Query<&LoadoutLineOf>,
+) {
+    let loadout = button_loadout(event.entity, &parent_query, &loadout_line_query);
+    commands.trigger(EquipLoadoutRequest { loadout });
+}
+
+/// Cycles the action the loadout is for through the actions that use equipment.
+fn on_activate_button_bind_loadout(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    loadout_line_query: Query<&LoadoutLineOf>,
+    loadout_query: Query<&Loadout>,
+) {
+    let loadout_entity = button_loadout(event.entity, &parent_query, &loadout_line_query);
+    let loadout = loadout_query.get(loadout_entity).expect("Entity of LoadoutLineOf must have a Loadout component.");
+
+    let actions = Action::LIST.iter().copied()
+    .filter(|&action| StatId::for_action(action).is_some())
+    .collect::<Vec<_>>();
+
+    commands.trigger(BindLoadoutRequest {
+        loadout: loadout_entity,
+        action: cycle_optional(&actions, loadout.action),
+    });
+}
+
+fn on_activate_button_delete_loadout(
+    event: On<Activate>,
+    mut commands: Commands,
+
+    parent_query: Query<&ChildOf>,
+    loadout_line_query: Query<&LoadoutLineOf>,
+) {
+    let loadout = button_loadout(event.entity, &parent_query, &loadout_line_query);
+    commands.trigger(DeleteLoadoutRequest { loadout });
+}
+
+/// Lists the items in the loadout.
+fn on_loadout_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+
+    loadout_line_query: Query<&LoadoutLineOf>,
+    loadout_query: Query<&Loadout>,
+    item_query: Query<&AffixiveItem>,
+) {
+    let loadout_entity = loadout_line_query.get(event.entity).expect("Loadout line must have a LoadoutLineOf component.").0;
+    let loadout = loadout_query.get(loadout_entity).expect("Entity of LoadoutLineOf must have a Loadout component.");
+
+    let content = commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+
+            border: px(1).all(),
+            padding: px(2).all(),
+
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+        BorderColor::all(Color::BLACK),
+    )).id();
+
+    for slot in item_db.slots() {
+        let Some(&item_entity) = loadout.items.get(&slot.id) else { continue; };
+
+        let item_name = item_query.get(item_entity)
+        .map_or("Missing".to_string(), |item| item.name().into_owned());
+
+        commands.spawn((
+            Text::new(format!("{}: {}", slot.name, item_name)),
+            TextFont { font_size: FontSize::Px(12.0), ..default() },
+            ChildOf(content),
+        ));
+    }
+
+    commands.trigger(ShowTooltip { content });
+}
+
+fn on_new_item_slot(
+    mut commands: Commands,
+
+    item_slot_query: Query<(Entity, &ItemSlot), Added<ItemSlot>>,
+    slot_list: Single<Entity, With<SlotList>>,
+) {
+    for (entity, item_slot) in item_slot_query.iter() {
+        commands.spawn_scene(bsn! {
+            slot(entity, item_slot.name.clone())
+            ChildOf({ *slot_list })
+        });
+    }
+}
+
+/// Shows the slot nodes of unlocked item slots with their names,
+/// in the order the item definitions list the slots.
+///
+/// The first slot node becomes the one items are equipped into when there is none.
+fn update_slot_nodes(
+    mut commands: Commands,
+
+    item_db: Res<ItemDatabase>,
+
+    slot_list: Single<Entity, With<SlotList>>,
+    children_query: Query<&Children>,
+    changed_item_slot_query: Query<(), Changed<ItemSlot>>,
+    added_slot_node_query: Query<(), Added<SlotNodeOf>>,
+    active_slot_query: Query<(), With<ActiveSlot>>,
+    item_slot_query: Query<&ItemSlot>,
+    mut slot_node_query: Query<(&SlotNodeOf, &mut Node)>,
+    mut slot_name_query: Query<(&SlotNameText, &mut Text)>,
+) {
+    if !item_db.is_changed() && changed_item_slot_query.is_empty() && added_slot_node_query.is_empty() && !active_slot_query.is_empty() {
+        return;
+    }
+
+    for (slot_node_of, mut node) in slot_node_query.iter_mut() {
+        let unlocked = item_slot_query.get(slot_node_of.0).is_ok_and(|item_slot| item_slot.unlocked);
+        node.display = if unlocked { Display::Flex } else { Display::None };
+    }
+
+    for (&SlotNameText(item_slot_entity), mut text) in slot_name_query.iter_mut() {
+        let Ok(item_slot) = item_slot_query.get(item_slot_entity) else { continue; };
+
+        if text.0 != item_slot.name {
+            text.0 = item_slot.name.clone();
+        }
+    }
+
+    let Ok(slot_nodes) = children_query.get(*slot_list) else { return; };
+    let slot_nodes = slot_nodes.to_vec();
+
+    let mut ordered_slot_nodes = slot_nodes.clone();
+    ordered_slot_nodes.sort_by_key(|&slot_node| {
+        slot_node_query.get(slot_node).ok()
+        .and_then(|(slot_node_of, _)| item_slot_query.get(slot_node_of.0).ok())
+        .and_then(|item_slot| item_db.slots().iter().position(|definition| definition.id == item_slot.id))
+    });
+
+    if ordered_slot_nodes != slot_nodes {
+        commands.entity(*slot_list).replace_children(&ordered_slot_nodes);
+    }
+
+    if active_slot_query.is_empty() && let Some(&first_slot_node) = ordered_slot_nodes.first() {
+        commands.entity(first_slot_node).insert(ActiveSlot);
+    }
+}
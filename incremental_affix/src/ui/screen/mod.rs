@@ -4,11 +4,13 @@ use bevy::text::FontSourceTemplate;
 use bevy::ui_widgets::{Activate, Button};
 
 use crate::incremental::action::KnownActions;
+use crate::incremental::job::JobKind;
 
 pub mod action;
 pub mod craft;
 pub mod inventory;
 pub mod population;
+pub mod stats;
 
 /// Kinds of screens in the game ui
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Component, Default, VariantDefaults)]
@@ -18,10 +20,11 @@ pub enum Screen {
     Population,
     Inventory,
     Craft,
+    Stats,
 }
 
 impl Screen {
-    pub const LIST: &[Self] = &[Self::Act, Self::Population, Self::Inventory, Self::Craft];
+    pub const LIST: &[Self] = &[Self::Act, Self::Population, Self::Inventory, Self::Craft, Self::Stats];
 }
 
 impl std::fmt::Display for Screen {
@@ -31,12 +34,15 @@ impl std::fmt::Display for Screen {
             Self::Population => "Population",
             Self::Inventory => "Inventory",
             Self::Craft => "Craft",
+            Self::Stats => "Stats",
         })
     }
 }
 
 pub fn screens_ui(
     known_actions: Res<KnownActions>,
+    unequipped_items: Vec<(Entity, String)>,
+    followers_assigned: Vec<(JobKind, u32)>,
 ) -> impl SceneList {
     (
         bsn! {
@@ -63,8 +69,9 @@ pub fn screens_ui(
             Children [
                 action::actions_screen(known_actions),
                 craft::crafting_screen(),
-                inventory::inventory_screen(),
-                population::population_screen(),
+                inventory::inventory_screen(unequipped_items),
+                population::population_screen(followers_assigned),
+                stats::stats_screen(),
             ]
         }
     )
@@ -15,7 +15,17 @@ impl Plugin for PopulationScreenPlugin {
     }
 }
 
-pub fn population_screen() -> impl Scene {
+pub fn population_screen(followers_assigned: Vec<(JobKind, u32)>) -> impl Scene {
+    let job_rows = JobKind::LIST.iter().copied()
+    .map(|job_kind| {
+        let followers = followers_assigned.iter()
+        .find(|&&(assigned_job_kind, _)| assigned_job_kind == job_kind)
+        .map_or(0, |&(_, followers)| followers);
+
+        job_row(job_kind, followers)
+    })
+    .collect::<Vec<_>>();
+
     bsn! {
         Node {
             display: Display::None,
@@ -28,19 +38,19 @@ pub fn population_screen() -> impl Scene {
         Children [
             screen_title("Jobs"),
             // ---
-            { JobKind::LIST.iter().copied().map(job_row).collect::<Vec<_>>() }
+            { job_rows }
         ]
     }
 }
 
-fn job_row (job_kind: JobKind) -> impl Scene {
+fn job_row (job_kind: JobKind, followers: u32) -> impl Scene {
     let job_row = bsn! {
         Node { flex_direction: FlexDirection::Row }
         template_value(job_kind)
 
         Children[
             Node { margin: { px(4).right() } }
-            Text::new("0")
+            Text::new(followers.to_string())
             TextColor::BLACK,
 
             Node { margin: { px(4).right() } }
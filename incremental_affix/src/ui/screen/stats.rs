@@ -0,0 +1,263 @@
+//! The stats screen.
+//!
+//! Lists the player's stats. Hovering an unequipped item listed beside them shows how equipping it would change them.
+
+use bevy::prelude::*;
+
+use crate::incremental::item::{affixive_item::AffixiveItem, item_set::EquippedSets, item_slot::ItemSlot};
+use crate::incremental::stats::{PlayerActionsStats, StatId, StatSources};
+use crate::ui::item::{ItemNameText, spawn_item_details};
+use crate::ui::screen::{Screen, screen_title};
+use crate::ui::stats::{display_stat_delta, display_stat_value, is_negligible_delta, spawn_stat_breakdown, stat_delta_color};
+use crate::ui::tooltip::{HideTooltip, ShowTooltip};
+
+pub struct StatsScreenPlugin;
+
+impl Plugin for StatsScreenPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(Update, (update_stat_values, (on_new_stats_item, hide_equipped_stats_items).chain()))
+        ;
+    }
+}
+
+/// The row showing a stat.
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct StatRow(StatId);
+
+/// The text showing the value of a stat.
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct StatValueText(StatId);
+
+/// The text showing how a stat would change by equipping the hovered item.
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct StatDeltaText(StatId);
+
+/// Marker component for the [Node] that contains the items to compare
+#[derive(Debug, Clone, Copy, Default, Component)]
+struct StatsItemList;
+
+#[derive(Debug, Clone, Component, FromTemplate)]
+#[relationship(relationship_target = CorrespondingStatsItemButton)]
+pub struct StatsItemButtonOf(pub Entity);
+
+/// Despawns the button when the item is despawned.
+#[derive(Debug, Clone, Component)]
+#[relationship_target(relationship = StatsItemButtonOf, linked_spawn)]
+pub struct CorrespondingStatsItemButton(Entity);
+
+pub fn stats_screen() -> impl Scene {
+    let stat_rows = StatId::LIST.iter().copied()
+    .map(stat_row)
+    .collect::<Vec<_>>();
+
+    bsn! {
+        Node {
+            display: Display::None,
+
+            flex_direction: FlexDirection::Column,
+        }
+        Screen::Stats
+
+        Children [
+            screen_title("Stats"),
+
+            Node {
+                flex_direction: FlexDirection::Row,
+            }
+            Children [
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    width: px(450),
+                }
+                Children [ { stat_rows } ],
+
+                Node {
+                    flex_direction: FlexDirection::Column,
+                }
+                StatsItemList
+                Children [
+                    Text::new("Hover an item to compare")
+                    TextColor::BLACK
+                    TextFont { font_size: px(12) }
+                ]
+            ]
+        ]
+    }
+}
+
+fn stat_row(stat: StatId) -> impl Scene {
+    bsn! {
+        Node {
+            flex_direction: FlexDirection::Row,
+        }
+        template_value(StatRow(stat))
+
+        on(on_stat_hover)
+        on(on_out_hide_tooltip)
+
+        Children [
+            Node {
+                width: px(220),
+            }
+            Text::new({ stat.to_string() })
+            TextColor::BLACK,
+
+            Node {
+                width: px(100),
+            }
+            Text::new("")
+            TextColor::BLACK
+            template_value(StatValueText(stat)),
+
+            Text::new("")
+            TextColor::BLACK
+            template_value(StatDeltaText(stat)),
+        ]
+    }
+}
+
+fn stats_item_button(item_entity: Entity, item_name: String) -> impl Scene {
+    bsn! {
+        Node {
+            border: px(1),
+            padding: px(2),
+            margin: px(2),
+        }
+        BorderColor::all(Color::BLACK)
+        Text(item_name)
+        TextColor::BLACK
+        ItemNameText(item_entity)
+
+        StatsItemButtonOf(item_entity)
+        on(on_stats_item_hover)
+        on(on_stats_item_out)
+    }
+}
+
+fn update_stat_values(
+    player_actions_stats: Res<PlayerActionsStats>,
+    mut value_text_query: Query<(&StatValueText, &mut Text)>,
+) {
+    if !player_actions_stats.is_changed() {
+        return;
+    }
+
+    for (&StatValueText(stat), mut text) in value_text_query.iter_mut() {
+        text.0 = display_stat_value(stat, player_actions_stats.get(stat));
+    }
+}
+
+fn on_new_stats_item(
+    mut commands: Commands,
+
+    item_query: Query<(Entity, &AffixiveItem), Added<AffixiveItem>>,
+    stats_item_list: Single<Entity, With<StatsItemList>>,
+) {
+    for (entity, item) in item_query.iter() {
+        commands.spawn_scene(bsn! {
+            stats_item_button(entity, item.name().into_owned())
+            ChildOf({ *stats_item_list })
+        });
+    }
+}
+
+/// Equipped items would not change anything, so only the others are listed.
+fn hide_equipped_stats_items(
+    changed_item_slot_query: Query<(), Changed<ItemSlot>>,
+    mut removed_item_slots: RemovedComponents<ItemSlot>,
+    added_button_query: Query<(), Added<StatsItemButtonOf>>,
+    item_slot_query: Query<&ItemSlot>,
+    mut button_query: Query<(&StatsItemButtonOf, &mut Node)>,
+) {
+    let removed_item_slot = removed_item_slots.read().count() > 0;
+
+    if changed_item_slot_query.is_empty() && !removed_item_slot && added_button_query.is_empty() {
+        return;
+    }
+
+    for (&StatsItemButtonOf(item_entity), mut node) in button_query.iter_mut() {
+        let equipped = item_slot_query.iter().any(|item_slot| item_slot.item == Some(item_entity));
+        let display = if equipped { Display::None } else { Display::Flex };
+
+        if node.display != display {
+            node.display = display;
+        }
+    }
+}
+
+fn on_stat_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    player_actions_stats: Res<PlayerActionsStats>,
+
+    stat_row_query: Query<&StatRow>,
+) {
+    let StatRow(stat) = *stat_row_query.get(event.entity).expect("Stat row must have a StatRow component.");
+
+    let content = spawn_stat_breakdown(commands.reborrow(), &player_actions_stats, &[stat]);
+    commands.trigger(ShowTooltip { content });
+}
+
+/// Shows the item and how equipping it would change each stat.
+fn on_stats_item_hover(
+    event: On<Pointer<Over>>,
+    mut commands: Commands,
+
+    player_actions_stats: Res<PlayerActionsStats>,
+    stat_sources: StatSources,
+    equipped_sets: EquippedSets,
+
+    stats_item_button_of_query: Query<&StatsItemButtonOf>,
+    item_query: Query<&AffixiveItem>,
+    item_slot_query: Query<&ItemSlot>,
+    mut delta_text_query: Query<(&StatDeltaText, &mut Text, &mut TextColor)>,
+) {
+    let item_entity = stats_item_button_of_query.get(event.entity).expect("Stats item button must have a StatsItemButtonOf component.").0;
+    let item = item_query.get(item_entity).expect("Entity of StatsItemButtonOf must have an AffixiveItem component.");
+
+    let content = spawn_item_details(commands.reborrow(), item, equipped_sets.details(item));
+    commands.trigger(ShowTooltip { content });
+
+    // Equipped items would not change anything.
+    if item_slot_query.iter().any(|item_slot| item_slot.item == Some(item_entity)) {
+        return;
+    }
+
+    let Some(equipped_stats) = stat_sources.calculate_equipping(item_entity) else { return; };
+
+    for (&StatDeltaText(stat), mut text, mut text_color) in delta_text_query.iter_mut() {
+        let before = player_actions_stats.get(stat);
+        let after = equipped_stats.get(stat);
+        let delta = after - before;
+
+        if is_negligible_delta(delta) {
+            text.0.clear();
+            continue;
+        }
+
+        text.0 = format!("-> {} ({})", display_stat_value(stat, after), display_stat_delta(stat, delta));
+        text_color.0 = stat_delta_color(delta);
+    }
+}
+
+fn on_stats_item_out(
+    _event: On<Pointer<Out>>,
+    mut commands: Commands,
+
+    mut delta_text_query: Query<&mut Text, With<StatDeltaText>>,
+) {
+    for mut text in delta_text_query.iter_mut() {
+        text.0.clear();
+    }
+
+    commands.trigger(HideTooltip);
+}
+
+fn on_out_hide_tooltip(
+    _event: On<Pointer<Out>>,
+    mut commands: Commands,
+) {
+    commands.trigger(HideTooltip);
+}
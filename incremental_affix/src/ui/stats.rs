@@ -0,0 +1,142 @@
+//! Showing player stats and where their values came from.
+
+use bevy::prelude::*;
+
+use crate::incremental::stats::{PlayerActionsStats, StatId};
+
+const STAT_INCREASE_COLOR: Color = Color::srgb(0.2, 0.8, 0.2);
+const STAT_DECREASE_COLOR: Color = Color::srgb(0.9, 0.2, 0.2);
+
+/// The value of the stat in the units the player thinks of it in.
+pub fn display_stat_value(stat: StatId, value: f64) -> String {
+    match stat {
+        StatId::WoodGain | StatId::StoneGain | StatId::HuntGain => format!("{:.2}/s", value),
+        StatId::WoodAffinityChance | StatId::StoneAffinityChance | StatId::HuntAffinityChance => format!("{:.0}%", value * 100.0),
+        StatId::WoodAffinityMultiplier | StatId::StoneAffinityMultiplier | StatId::HuntAffinityMultiplier => format!("x{:.2}", value),
+        StatId::WoodAffinityTime | StatId::StoneAffinityTime | StatId::HuntAffinityTime => format!("{:.2}s", value),
+    }
+}
+
+/// The change in the stat's value with its sign, e.g. "+0.30/s".
+pub fn display_stat_delta(stat: StatId, delta: f64) -> String {
+    let sign = if delta > 0.0 { "+" } else { "" };
+    format!("{}{}", sign, display_stat_value(stat, delta))
+}
+
+/// Color for showing a change in a stat.
+pub fn stat_delta_color(delta: f64) -> Color {
+    if delta > 0.0 { STAT_INCREASE_COLOR } else { STAT_DECREASE_COLOR }
+}
+
+/// Whether the change is too small to show.
+pub fn is_negligible_delta(delta: f64) -> bool {
+    delta.abs() < 1e-9
+}
+
+/// Spawns tooltip content listing how every stat would change, e.g. "+0.30/s Wood per second".
+pub fn spawn_stat_changes(
+    mut commands: Commands,
+
+    before: &PlayerActionsStats,
+    after: &PlayerActionsStats,
+) -> Entity {
+    let changes_box = commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+
+            border: px(1).all(),
+            padding: px(2).all(),
+
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+        BorderColor::all(Color::BLACK),
+    )).id();
+
+    let changes = StatId::LIST.iter().copied()
+    .map(|stat| (stat, after.get(stat) - before.get(stat)))
+    .filter(|&(_, delta)| !is_negligible_delta(delta))
+    .collect::<Vec<_>>();
+
+    if changes.is_empty() {
+        commands.spawn((
+            Text::new("No change to stats"),
+            TextFont { font_size: FontSize::Px(12.0), ..default() },
+            ChildOf(changes_box),
+        ));
+    }
+
+    for (stat, delta) in changes {
+        commands.spawn((
+            Text::new(format!("{} {}", display_stat_delta(stat, delta), stat)),
+            TextFont { font_size: FontSize::Px(12.0), ..default() },
+            TextColor(stat_delta_color(delta)),
+            ChildOf(changes_box),
+        ));
+    }
+
+    changes_box
+}
+
+/// Spawns tooltip content listing the stats with every contribution to each.
+pub fn spawn_stat_breakdown(
+    mut commands: Commands,
+
+    stats: &PlayerActionsStats,
+    stat_ids: &[StatId],
+) -> Entity {
+    let breakdown_box = commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+
+            min_width: px(160),
+
+            border: px(1).all(),
+            padding: px(2).all(),
+
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+        BorderColor::all(Color::BLACK),
+    )).id();
+
+    for &stat in stat_ids {
+        commands.spawn((
+            Node {
+                ..default()
+            },
+
+            children![(
+                Text::new(format!("{}: {}", stat, display_stat_value(stat, stats.get(stat)))),
+                TextFont { font_size: FontSize::Px(14.0), ..default() }
+            )],
+
+            ChildOf(breakdown_box),
+        ));
+
+        let breakdown = stats.breakdown(stat);
+        let lines = if breakdown.is_empty() {
+            vec!["No modifiers".to_string()]
+        } else {
+            breakdown.iter().map(ToString::to_string).collect()
+        };
+
+        for line in lines {
+            commands.spawn((
+                Node {
+                    margin: px(8).left(),
+                    ..default()
+                },
+
+                children![(
+                    Text::new(line),
+                    TextFont { font_size: FontSize::Px(12.0), ..default() }
+                )],
+
+                ChildOf(breakdown_box),
+            ));
+        }
+    }
+
+    breakdown_box
+}
@@ -99,4 +99,38 @@ fn on_hide_tooltip(
 
     *visibility = Visibility::Hidden;
     *tooltip = Tooltip::Hidden;
-}
\ No newline at end of file
+}
+
+/// Spawns the box of a tooltip with a title, a description, and a footer for things like costs.
+///
+/// Returns the box to show with [ShowTooltip].
+pub fn spawn_tooltip_box(mut commands: Commands, title: impl Into<String>, description: impl Into<String>, footer: impl Into<String>) -> Entity {
+    commands.spawn((
+        Node {
+            flex_direction: FlexDirection::Column,
+
+            width: px(160),
+
+            border: px(1).all(),
+
+            ..default()
+        },
+        BackgroundColor(Color::srgb(0.4, 0.4, 0.4)),
+        BorderColor::all(Color::BLACK),
+
+        children![
+            (
+                Text::new(title),
+                TextFont { font_size: FontSize::Px(16.0), ..default() }
+            ),
+            (
+                Text::new(description),
+                TextFont { font_size: FontSize::Px(14.0), ..default() }
+            ),
+            (
+                Text::new(footer),
+                TextFont { font_size: FontSize::Px(12.0), ..default() }
+            ),
+        ],
+    )).id()
+}